@@ -24,8 +24,26 @@ fn main() {
     remove_schemas(&out_dir).unwrap();
 
     export_schema(&schema_for!(InitMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(DepositResponse), &out_dir);
     export_schema(&schema_for!(DisputeResponse), &out_dir);
+    export_schema(&schema_for!(HoldingsResponse), &out_dir);
+    export_schema(&schema_for!(ChannelStatusResponse), &out_dir);
+    export_schema(&schema_for!(SolvencyResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(DepositResult), &out_dir);
+    export_schema(&schema_for!(IbcDepositResult), &out_dir);
+    export_schema(&schema_for!(DisputeResult), &out_dir);
+    export_schema(&schema_for!(ConcludeResult), &out_dir);
+    export_schema(&schema_for!(WithdrawResult), &out_dir);
+    export_schema(&schema_for!(SettleResult), &out_dir);
+    export_schema(&schema_for!(CheckpointResult), &out_dir);
+    export_schema(&schema_for!(CancelDisputeResult), &out_dir);
+    export_schema(&schema_for!(NovationResult), &out_dir);
+    export_schema(&schema_for!(RolloverResult), &out_dir);
+    export_schema(&schema_for!(SpliceResult), &out_dir);
+    export_schema(&schema_for!(RevealResult), &out_dir);
+    export_schema(&schema_for!(BatchResult), &out_dir);
 }
@@ -14,29 +14,101 @@
 
 //! Core functionality for controlling the on-chain part of state channels.
 use crate::{
-    crypto::{OffIdentity, Sig},
+    crypto::{hash_bytes, Hasher, OnIdentity, Sig},
     ensure,
     error::ContractError,
-    msg::{DepositResponse, DisputeResponse, ExecuteMsg, InitMsg, QueryMsg},
-    storage::{DEPOSITS, DISPUTES},
+    msg::{
+        BatchResult, CancelDisputeResult, ChannelPhase, ChannelStatusResponse, CheckpointResult,
+        ConcludeResult, ConfigResponse, DenomSolvency, DepositResponse, DepositResult,
+        DisputeResponse, DisputeResult, ExecuteMsg, Holding, HoldingsResponse, IbcDepositResult,
+        InitMsg, MigrateMsg, NovationResult, Operation, PartStatus, QueryMsg, RevealResult,
+        RolloverResult, SettleResult, SignedWatcherAuth, SignedWithdrawal, SolvencyResponse,
+        SpliceResult, WithdrawResult,
+    },
+    storage::{
+        add_liabilities, close_channel, ensure_checkpoint, ensure_not_closed, load_authority,
+        load_config, load_deposit, load_liabilities, load_signers, mark_withdrawn,
+        register_channel, remove_deposit, reset_liabilities, save_deposit, sub_liabilities,
+        use_dispute_nonce, use_novation_nonce, AUTHORITIES, BONDS, CHECKPOINTS, CLOSED, CONFIG,
+        DISPUTES, FUNDING_IDS, HOLDINGS, LEGACY_DISPUTES, LIABILITIES, PARAMS, PREIMAGES, REWARDS,
+        WITHDRAWN,
+    },
     types::*,
 };
 use cosmwasm_std::{
-    entry_point, to_binary, BankMsg::Send, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    Storage, Timestamp,
+    entry_point, to_binary, BankMsg::Send, Binary, Coin, Deps, DepsMut, Env, Event, MessageInfo,
+    Order, Response, Storage, Timestamp,
 };
-use std::{ops::Add, result::Result};
+use sha2::Digest;
+use std::result::Result;
 
 /// Handles all [InitMsg] messages.
 ///
 /// Can be used to initialize the contract, which is only done once.
 #[entry_point]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
-    _msg: InitMsg,
+    msg: InitMsg,
 ) -> Result<Response, ContractError> {
+    let admin = match msg.admin {
+        Some(admin) => Some(deps.api.addr_validate(&admin)?),
+        None => None,
+    };
+    msg.limits.validate()?;
+    let config = Config {
+        compact_disputes: msg.compact_disputes,
+        admin,
+        allowed_denoms: msg.allowed_denoms,
+        funding_caps: msg.funding_caps,
+        denom_caps: msg.denom_caps,
+        limits: msg.limits,
+        dispute_bond: msg.dispute_bond,
+        ..Default::default()
+    };
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Default::default())
+}
+
+/// Handles all [MigrateMsg] messages.
+///
+/// Converts disputes from the [crate::storage::LegacyDispute] layout,
+/// applies the new [Config::compact_disputes] setting, registers the
+/// channels in [MigrateMsg::channels] and recomputes the liabilities.
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let mut config = load_config(deps.storage)?;
+    config.compact_disputes = msg.compact_disputes;
+    CONFIG.save(deps.storage, &config)?;
+
+    let channel_ids: Vec<ChannelId> = DISPUTES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| key.as_slice().into())
+        .collect();
+    for channel_id in channel_ids {
+        let dispute = match LEGACY_DISPUTES.load(deps.storage, channel_id.clone()) {
+            Ok(legacy) => Dispute {
+                state: RegisteredState::new(&legacy.state, config.compact_disputes)?,
+                timeout: legacy.timeout,
+                concluded: legacy.concluded,
+            },
+            // Already in the current layout.
+            Err(_) => {
+                let mut dispute = DISPUTES.load(deps.storage, channel_id.clone())?;
+                if config.compact_disputes {
+                    dispute.state.state = None;
+                }
+                dispute
+            }
+        };
+        DISPUTES.save(deps.storage, channel_id, &dispute)?;
+    }
+    for params in msg.channels.iter() {
+        params.validate()?;
+        register_channel(deps.storage, params)?;
+    }
+    reset_liabilities(deps.storage)?;
     Ok(Default::default())
 }
 
@@ -50,34 +122,281 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Deposit(funding_id) => deposit(deps.storage, info, funding_id),
+        ExecuteMsg::IbcDeposit {
+            funding_id,
+            refund_to,
+        } => ibc_deposit(deps, info, &funding_id, &refund_to),
         ExecuteMsg::Dispute {
             params,
             state,
             sigs,
-        } => dispute(deps, env.block.time, &params, &state, &sigs),
+            arbiter_sig,
+            watcher,
+        } => dispute(
+            deps,
+            env.block.time,
+            &info,
+            &params,
+            &state,
+            &sigs,
+            arbiter_sig.as_ref(),
+            watcher.as_ref(),
+        ),
         ExecuteMsg::Conclude {
             params,
             state,
             sigs,
-        } => conclude(deps, env.block.time, &params, &state, &sigs),
-        ExecuteMsg::Withdraw { withdrawal, sig } => withdraw(deps, &withdrawal, &sig),
+            arbiter_sig,
+        } => conclude(
+            deps,
+            env.block.time,
+            &info.sender,
+            &params,
+            &state,
+            &sigs,
+            arbiter_sig.as_ref(),
+        ),
+        ExecuteMsg::ConcludeDispute { channel_id, state } => conclude_dispute(
+            deps,
+            env.block.time,
+            &info.sender,
+            &channel_id,
+            state.as_ref(),
+        ),
+        ExecuteMsg::ConcludeExpired { params, state } => {
+            conclude_expired(deps, env.block.time, &info.sender, &params, state.as_ref())
+        }
+        ExecuteMsg::Checkpoint {
+            params,
+            state,
+            sigs,
+        } => checkpoint(deps, env.block.time, &params, &state, &sigs),
+        ExecuteMsg::ExtendDispute {
+            params,
+            extension,
+            sigs,
+        } => extend_dispute(deps, env.block.time, &params, &extension, &sigs),
+        ExecuteMsg::CancelDispute {
+            params,
+            cancellation,
+            sigs,
+        } => cancel_dispute(deps, &params, &cancellation, &sigs),
+        ExecuteMsg::Withdraw { withdrawal, sig } => withdraw(deps, &info.sender, &withdrawal, &sig),
+        ExecuteMsg::Novate {
+            params,
+            novation,
+            sigs,
+        } => novate(deps, &params, &novation, &sigs),
+        ExecuteMsg::Rollover {
+            old_channel,
+            new_params,
+            sigs,
+        } => rollover(deps, &old_channel, &new_params, &sigs),
+        ExecuteMsg::Settle {
+            params,
+            state,
+            sigs,
+            withdrawals,
+        } => settle(
+            deps,
+            env.block.time,
+            &info.sender,
+            &params,
+            &state,
+            &sigs,
+            withdrawals,
+        ),
+        ExecuteMsg::Splice {
+            params,
+            splice,
+            sigs,
+        } => splice_channel(deps, &info, &params, &splice, &sigs),
+        ExecuteMsg::RevealPreimage { preimage } => {
+            reveal_preimage(deps.storage, env.block.time, &preimage)
+        }
+        ExecuteMsg::SetPaused { deposits, disputes } => set_paused(deps, info, deposits, disputes),
+        ExecuteMsg::TransferAdmin { admin } => {
+            let admin = deps.api.addr_validate(&admin)?;
+            set_admin(deps, info, Some(admin))
+        }
+        ExecuteMsg::RenounceAdmin {} => set_admin(deps, info, None),
+        ExecuteMsg::UpdateConfig {
+            allowed_denoms,
+            funding_caps,
+            denom_caps,
+        } => update_config(deps, info, allowed_denoms, funding_caps, denom_caps),
+        ExecuteMsg::SetChannelLimits { limits } => set_channel_limits(deps, info, limits),
+        ExecuteMsg::SetDisputeBond { dispute_bond } => set_dispute_bond(deps, info, dispute_bond),
+        ExecuteMsg::Batch(ops) => batch(deps, env, info, ops),
+    }
+}
+
+/// See [crate::msg::ExecuteMsg::Batch].
+fn batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ops: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    // The attached funds must be allocated completely.
+    let mut allocated = WrappedBalance::default();
+    for op in ops.iter() {
+        match op {
+            Operation::Deposit { amount, .. } | Operation::Dispute { bond: amount, .. } => {
+                allocated = allocated.checked_add(&amount.clone().into())?;
+            }
+            Operation::Splice { splice, .. } => {
+                allocated = allocated.checked_add(&splice_funds(splice)?)?;
+            }
+            _ => {}
+        }
+    }
+    ensure!(
+        allocated == WrappedBalance::from(info.funds.clone()),
+        ContractError::BatchFundsMismatch {}
+    );
+
+    let mut res = Response::new();
+    let mut data = vec![];
+    for op in ops {
+        let now = env.block.time;
+        let op_res = match op {
+            Operation::Deposit { funding_id, amount } => {
+                let info = MessageInfo {
+                    sender: info.sender.clone(),
+                    funds: amount,
+                };
+                deposit(deps.storage, info, funding_id)
+            }
+            Operation::Dispute {
+                params,
+                state,
+                sigs,
+                arbiter_sig,
+                watcher,
+                bond,
+            } => {
+                let info = MessageInfo {
+                    sender: info.sender.clone(),
+                    funds: bond,
+                };
+                dispute(
+                    deps.branch(),
+                    now,
+                    &info,
+                    &params,
+                    &state,
+                    &sigs,
+                    arbiter_sig.as_ref(),
+                    watcher.as_ref(),
+                )
+            }
+            Operation::Conclude {
+                params,
+                state,
+                sigs,
+                arbiter_sig,
+            } => conclude(
+                deps.branch(),
+                now,
+                &info.sender,
+                &params,
+                &state,
+                &sigs,
+                arbiter_sig.as_ref(),
+            ),
+            Operation::ConcludeDispute { channel_id, state } => conclude_dispute(
+                deps.branch(),
+                now,
+                &info.sender,
+                &channel_id,
+                state.as_ref(),
+            ),
+            Operation::ConcludeExpired { params, state } => {
+                conclude_expired(deps.branch(), now, &info.sender, &params, state.as_ref())
+            }
+            Operation::Checkpoint {
+                params,
+                state,
+                sigs,
+            } => checkpoint(deps.branch(), now, &params, &state, &sigs),
+            Operation::ExtendDispute {
+                params,
+                extension,
+                sigs,
+            } => extend_dispute(deps.branch(), now, &params, &extension, &sigs),
+            Operation::CancelDispute {
+                params,
+                cancellation,
+                sigs,
+            } => cancel_dispute(deps.branch(), &params, &cancellation, &sigs),
+            Operation::Withdraw { withdrawal, sig } => {
+                withdraw(deps.branch(), &info.sender, &withdrawal, &sig)
+            }
+            Operation::Novate {
+                params,
+                novation,
+                sigs,
+            } => novate(deps.branch(), &params, &novation, &sigs),
+            Operation::Rollover {
+                old_channel,
+                new_params,
+                sigs,
+            } => rollover(deps.branch(), &old_channel, &new_params, &sigs),
+            Operation::Settle {
+                params,
+                state,
+                sigs,
+                withdrawals,
+            } => settle(
+                deps.branch(),
+                now,
+                &info.sender,
+                &params,
+                &state,
+                &sigs,
+                withdrawals,
+            ),
+            Operation::Splice {
+                params,
+                splice,
+                sigs,
+            } => {
+                let info = MessageInfo {
+                    sender: info.sender.clone(),
+                    funds: splice_funds(&splice)?.into(),
+                };
+                splice_channel(deps.branch(), &info, &params, &splice, &sigs)
+            }
+            Operation::RevealPreimage { preimage } => reveal_preimage(deps.storage, now, &preimage),
+        }?;
+        data.push(op_res.data);
+        res = res
+            .add_submessages(op_res.messages)
+            .add_attributes(op_res.attributes)
+            .add_events(op_res.events);
     }
+    Ok(res.set_data(to_binary(&BatchResult(data))?))
 }
 
 /// Handles all [QueryMsg] messages.
 ///
 /// Can be used to query the contract state.
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Deposit(fid) => query_deposit(deps, fid),
         QueryMsg::Dispute(cid) => query_dispute(deps, cid),
+        QueryMsg::Holdings(cid) => query_holdings(deps, cid),
+        QueryMsg::ChannelStatus { params } => query_channel_status(deps, env.block.time, &params),
+        QueryMsg::Solvency {} => query_solvency(deps, env),
+        QueryMsg::Config {} => Ok(to_binary(&ConfigResponse(load_config(deps.storage)?))?),
     }
 }
 
 /// See [crate::msg::QueryMsg::Deposit].
 fn query_deposit(deps: Deps, fid: FundingId) -> Result<Binary, ContractError> {
-    match DEPOSITS.may_load(deps.storage, fid)? {
+    match load_deposit(deps.storage, &fid)? {
         Some(deposit) => {
             let out = to_binary(&DepositResponse(deposit.0 .0))?;
             Ok(out)
@@ -97,40 +416,305 @@ fn query_dispute(deps: Deps, cid: ChannelId) -> Result<Binary, ContractError> {
     }
 }
 
+/// See [crate::msg::QueryMsg::Holdings].
+fn query_holdings(deps: Deps, cid: ChannelId) -> Result<Binary, ContractError> {
+    ensure!(
+        PARAMS.has(deps.storage, cid.clone()),
+        ContractError::UnknownChannel {}
+    );
+    let holdings = HOLDINGS
+        .prefix(cid)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (key, deposit) = item?;
+            let mut index = [0u8; 2];
+            index.copy_from_slice(&key);
+            Ok(Holding {
+                part: PartIdx::from_be_bytes(index),
+                amount: deposit.into(),
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    Ok(to_binary(&HoldingsResponse(holdings))?)
+}
+
+/// See [crate::msg::QueryMsg::ChannelStatus].
+fn query_channel_status(
+    deps: Deps,
+    now: Timestamp,
+    params: &Params,
+) -> Result<Binary, ContractError> {
+    let cid = params.channel_id()?;
+    if let Some(version) = CLOSED.may_load(deps.storage, cid.clone())? {
+        let parts = params
+            .participants
+            .iter()
+            .map(|_| PartStatus {
+                holding: vec![],
+                withdrawn: true,
+            })
+            .collect();
+        return Ok(to_binary(&ChannelStatusResponse {
+            phase: ChannelPhase::Withdrawn,
+            version: Some(version),
+            timeout_in: None,
+            parts,
+        })?);
+    }
+
+    let (phase, version, timeout_in) = match DISPUTES.may_load(deps.storage, cid.clone())? {
+        None => (ChannelPhase::Open, None, None),
+        Some(dispute) if dispute.concluded => {
+            (ChannelPhase::Concluded, Some(dispute.state.version), None)
+        }
+        Some(dispute) => {
+            let left = dispute.timeout.seconds().saturating_sub(now.seconds());
+            (
+                ChannelPhase::Disputed,
+                Some(dispute.state.version),
+                Some(left.into()),
+            )
+        }
+    };
+    let parts = params
+        .participants
+        .iter()
+        .enumerate()
+        .map(|(i, part)| {
+            let fid = calc_funding_id(&cid, part)?;
+            let holding = load_deposit(deps.storage, &fid)?.unwrap_or_default();
+            let key = (cid.clone(), (i as PartIdx).into());
+            Ok(PartStatus {
+                holding: holding.into(),
+                withdrawn: WITHDRAWN.has(deps.storage, key),
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    Ok(to_binary(&ChannelStatusResponse {
+        phase,
+        version,
+        timeout_in,
+        parts,
+    })?)
+}
+
+/// See [crate::msg::QueryMsg::Solvency].
+fn query_solvency(deps: Deps, env: Env) -> Result<Binary, ContractError> {
+    let denoms = LIABILITIES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, liabilities) = item?;
+            let denom = String::from_utf8(denom)
+                .map_err(|_| ContractError::InternalError("Invalid denom".into()))?;
+            let balance = deps
+                .querier
+                .query_balance(env.contract.address.clone(), denom.clone())?
+                .amount;
+            Ok(DenomSolvency {
+                denom,
+                liabilities,
+                balance,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    let solvent = denoms.iter().all(|d| d.balance >= d.liabilities);
+    Ok(to_binary(&SolvencyResponse { solvent, denoms })?)
+}
+
 /// See [crate::msg::ExecuteMsg::Deposit].
 fn deposit(
     storage: &mut dyn Storage,
     info: MessageInfo,
     funding_id: FundingId,
 ) -> Result<Response, ContractError> {
-    DEPOSITS.update(storage, funding_id, |holding| -> Result<_, ContractError> {
-        Ok(holding.unwrap_or_default().add(&info.funds.into()))
-    })?;
-    Ok(Default::default())
+    let data = credit_deposit(storage, funding_id, &info.funds.into())?;
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+/// See [crate::msg::ExecuteMsg::IbcDeposit].
+fn ibc_deposit(
+    deps: DepsMut,
+    info: MessageInfo,
+    funding_id: &str,
+    refund_to: &str,
+) -> Result<Response, ContractError> {
+    let receiver = deps.api.addr_validate(refund_to)?;
+    let funds = WrappedBalance::from(info.funds.clone());
+    let credited =
+        parse_funding_id(funding_id).and_then(|fid| credit_deposit(deps.storage, fid, &funds));
+    let (data, res) = match credited {
+        Ok(deposit) => (IbcDepositResult::Deposited(deposit), Response::new()),
+        Err(err) => {
+            let mut res = Response::new();
+            if !info.funds.is_empty() {
+                res = res.add_message(Send {
+                    to_address: receiver.to_string(),
+                    amount: info.funds.clone(),
+                });
+            }
+            let data = IbcDepositResult::Refunded {
+                receiver,
+                amount: info.funds,
+                reason: err.to_string(),
+            };
+            (data, res)
+        }
+    };
+    Ok(res.set_data(to_binary(&data)?))
+}
+
+/// Parses a base64 encoded [FundingId] as found in an ICS-20 memo.
+fn parse_funding_id(funding_id: &str) -> Result<FundingId, ContractError> {
+    let fid = Binary::from_base64(funding_id).map_err(|_| ContractError::InvalidFundingId {})?;
+    ensure!(
+        fid.len() == Hasher::output_size(),
+        ContractError::InvalidFundingId {}
+    );
+    Ok(WrappedBinary(fid))
+}
+
+/// Adds `funds` to the deposit of `funding_id`.
+///
+/// All checks happen before the first write, so a rejected deposit leaves
+/// the storage untouched.
+fn credit_deposit(
+    storage: &mut dyn Storage,
+    funding_id: FundingId,
+    funds: &WrappedBalance,
+) -> Result<DepositResult, ContractError> {
+    let config = load_config(storage)?;
+    ensure!(!config.deposits_paused, ContractError::Paused {});
+    if let Some((channel_id, _)) = FUNDING_IDS.may_load(storage, funding_id.clone())? {
+        ensure_not_closed(storage, &channel_id)?;
+    }
+    let holding = load_deposit(storage, &funding_id)?
+        .unwrap_or_default()
+        .checked_add(funds)?;
+    let liabilities = load_liabilities(storage, funds)?.checked_add(funds)?;
+    enforce_deposit_limits(&config, funds, &holding, &liabilities)?;
+    save_deposit(storage, &funding_id, &holding)?;
+    add_liabilities(storage, funds)?;
+
+    Ok(DepositResult {
+        funding_id,
+        holding: holding.into(),
+    })
+}
+
+/// Enforces the limits of the `config` on `funds` that were added to
+/// `holding`.
+///
+/// `liabilities` must contain the [LIABILITIES] of the denoms in `funds`
+/// including `funds`.
+fn enforce_deposit_limits(
+    config: &Config,
+    funds: &WrappedBalance,
+    holding: &WrappedBalance,
+    liabilities: &WrappedBalance,
+) -> Result<(), ContractError> {
+    for coin in funds.0 .0.iter() {
+        let denom = &coin.denom;
+        ensure!(
+            config.allows_denom(denom),
+            ContractError::DenomNotAllowed {
+                denom: denom.clone()
+            }
+        );
+        if let Some(cap) = config.funding_cap(denom) {
+            ensure!(
+                find_amount(&holding.0 .0, denom).unwrap_or_default() <= cap,
+                ContractError::FundingCapExceeded {
+                    denom: denom.clone()
+                }
+            );
+        }
+        if let Some(cap) = config.denom_cap(denom) {
+            ensure!(
+                find_amount(&liabilities.0 .0, denom).unwrap_or_default() <= cap,
+                ContractError::DenomCapExceeded {
+                    denom: denom.clone()
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `params` are well-formed and enforces the [ChannelLimits] of
+/// the `config` on `params` and `state` if the channel is not registered yet.
+///
+/// Registered channels are exempt so that later changes of the limits
+/// cannot lock their funds.
+fn validate_new_channel(
+    storage: &dyn Storage,
+    config: &Config,
+    params: &Params,
+    state: Option<&State>,
+) -> Result<(), ContractError> {
+    params.validate()?;
+    if PARAMS.has(storage, params.channel_id()?) {
+        return Ok(());
+    }
+    params.validate_limits(&config.limits)?;
+    if let Some(state) = state {
+        state.validate_limits(&config.limits)?;
+    }
+    Ok(())
 }
 
 /// See [crate::msg::ExecuteMsg::Dispute].
+#[allow(clippy::too_many_arguments)]
 fn dispute(
-    deps: DepsMut,
+    mut deps: DepsMut,
     now: Timestamp,
+    info: &MessageInfo,
     params: &Params,
     state: &State,
     sigs: &[Sig],
+    arbiter_sig: Option<&Sig>,
+    watcher: Option<&SignedWatcherAuth>,
 ) -> Result<Response, ContractError> {
     ensure!(!state.finalized, ContractError::StateFinal {});
-    state.verify_fully_signed(params, sigs, deps.api)?;
+    verify_state(deps.as_ref(), params, state, sigs, arbiter_sig)?;
     let channel_id = state.channel_id.clone();
+    ensure_not_closed(deps.storage, &channel_id)?;
+    ensure_checkpoint(deps.storage, &channel_id, state.version)?;
+    let config = load_config(deps.storage)?;
+    validate_new_channel(deps.storage, &config, params, Some(state))?;
 
     match DISPUTES.may_load(deps.storage, channel_id.clone())? {
         None => {
+            ensure!(
+                arbiter_sig.is_none(),
+                ContractError::ArbitrationWithoutDispute {}
+            );
+            // Running disputes can still be refuted while paused.
+            ensure!(!config.disputes_paused, ContractError::Paused {});
+            // Running disputes can still be refuted after the expiry.
+            ensure!(!params.is_expired(now), ContractError::ChannelExpired {});
+            register_channel(deps.storage, params)?;
+            // Lock the bond until the dispute is refuted or concluded.
+            let bond = config.dispute_bond(params);
+            ensure!(
+                WrappedBalance::from(info.funds.clone()) == bond,
+                ContractError::WrongBond {}
+            );
+            if !bond.0.is_empty() {
+                let bond = Bond {
+                    owner: info.sender.clone(),
+                    amount: bond.into(),
+                };
+                BONDS.save(deps.storage, channel_id.clone(), &bond)?;
+                add_liabilities(deps.storage, &bond.amount.into())?;
+            }
             let timeout = now.plus_seconds(params.dispute_duration.u64());
             let dispute = Dispute {
-                state: state.clone(),
+                state: RegisteredState::new(state, config.compact_disputes)?,
                 timeout,
                 concluded: false,
             };
-            DISPUTES.save(deps.storage, channel_id, &dispute)?;
-            Ok(Default::default())
+            DISPUTES.save(deps.storage, channel_id.clone(), &dispute)?;
+            dispute_response(channel_id, &dispute)
         }
         Some(Dispute {
             state: old_state,
@@ -143,76 +727,679 @@ fn dispute(
                 ContractError::DisputeVersionTooLow {}
             );
             ensure!(now < timeout, ContractError::DisputeTimedOut {});
+            ensure!(info.funds.is_empty(), ContractError::WrongBond {});
+            if arbiter_sig.is_some() {
+                ensure_arbitrable(now, params, timeout)?;
+            }
+            // Only refutations are rewarded.
+            if let Some(watcher) = watcher {
+                record_reward(deps.branch(), now, &info.sender, params, watcher)?;
+            }
+            let res = release_bond(deps.storage, Response::new(), &channel_id, &info.sender)?;
 
             let dispute = Dispute {
-                state: state.clone(),
+                state: RegisteredState::new(state, config.compact_disputes)?,
                 timeout,
                 concluded: false,
             };
-            DISPUTES.save(deps.storage, channel_id, &dispute)?;
-            Ok(Default::default())
+            DISPUTES.save(deps.storage, channel_id.clone(), &dispute)?;
+            let mut res =
+                dispute_response(channel_id.clone(), &dispute)?.add_submessages(res.messages);
+            if arbiter_sig.is_some() {
+                res = res.add_event(arbitrate_event("dispute", &channel_id, state.version));
+            }
+            Ok(res)
         }
     }
 }
 
+/// Verifies that all participants signed `state`, or only the
+/// [Params::arbiter] if `arbiter_sig` is given.
+fn verify_state(
+    deps: Deps,
+    params: &Params,
+    state: &State,
+    sigs: &[Sig],
+    arbiter_sig: Option<&Sig>,
+) -> Result<(), ContractError> {
+    match arbiter_sig {
+        Some(sig) => state.verify_arbitrated(params, sigs, sig, deps.api),
+        None => {
+            let signers = load_signers(deps.storage, params)?;
+            state.verify_fully_signed(params, &signers, sigs, deps.api)
+        }
+    }
+}
+
+/// Ensures that the [Arbiter::delay] of the dispute with `timeout` is over.
+fn ensure_arbitrable(
+    now: Timestamp,
+    params: &Params,
+    timeout: Timestamp,
+) -> Result<(), ContractError> {
+    let arbiter = params
+        .arbiter
+        .as_ref()
+        .ok_or(ContractError::ArbiterNotSet {})?;
+    // Disputes keep their timeout when refuted, extensions push the
+    // arbitration back as well.
+    // Checks `now >= timeout - dispute_duration + delay` without overflows.
+    let nanos = |seconds: Seconds| u128::from(seconds.u64()) * 1_000_000_000;
+    ensure!(
+        u128::from(now.nanos()) + nanos(params.dispute_duration)
+            >= u128::from(timeout.nanos()) + nanos(arbiter.delay),
+        ContractError::ArbitrationTooEarly {}
+    );
+    Ok(())
+}
+
+/// Returns the event that reports an arbitrated `action` on a channel.
+fn arbitrate_event(action: &str, channel_id: &ChannelId, version: Version) -> Event {
+    Event::new("arbitrate")
+        .add_attribute("action", action)
+        .add_attribute("channel_id", channel_id.0.to_base64())
+        .add_attribute("version", version.to_string())
+}
+
+/// Pays the [BONDS] entry of a channel to `to`, if any.
+fn release_bond(
+    storage: &mut dyn Storage,
+    res: Response,
+    channel_id: &ChannelId,
+    to: &OnIdentity,
+) -> Result<Response, ContractError> {
+    let bond = match BONDS.may_load(storage, channel_id.clone())? {
+        Some(bond) => WrappedBalance::from(bond.amount),
+        None => return Ok(res),
+    };
+    BONDS.remove(storage, channel_id.clone());
+    sub_liabilities(storage, &bond)?;
+    Ok(send(res, to, &bond))
+}
+
+/// Returns the bond of a concluded channel to the opener of the dispute,
+/// or pays it to `sender` if the dispute was refuted by the concluded
+/// `version`.
+fn settle_bond(
+    storage: &mut dyn Storage,
+    res: Response,
+    channel_id: &ChannelId,
+    sender: &OnIdentity,
+    registered: Option<Version>,
+    version: Version,
+) -> Result<Response, ContractError> {
+    let owner = match BONDS.may_load(storage, channel_id.clone())? {
+        Some(bond) => bond.owner,
+        None => return Ok(res),
+    };
+    match registered {
+        Some(registered) if version > registered => release_bond(storage, res, channel_id, sender),
+        _ => release_bond(storage, res, channel_id, &owner),
+    }
+}
+
+/// Records the reward of a watcher that refuted a dispute.
+///
+/// Replaces earlier rewards for the same participant, so that a participant
+/// pays at most one reward per channel.
+fn record_reward(
+    deps: DepsMut,
+    now: Timestamp,
+    sender: &OnIdentity,
+    params: &Params,
+    SignedWatcherAuth { auth, sig }: &SignedWatcherAuth,
+) -> Result<(), ContractError> {
+    ensure!(
+        &auth.watcher == sender && auth.channel_id == params.channel_id()?,
+        ContractError::InvalidWatcher {}
+    );
+    ensure!(now < auth.expiry, ContractError::WatcherAuthExpired {});
+    let index = params
+        .participants
+        .iter()
+        .position(|part| part == &auth.part)
+        .ok_or(ContractError::WrongParticipant {})?;
+    let from = load_authority(deps.storage, &auth.channel_id, &auth.part)?;
+    auth.verify(sig, &from, deps.api)?;
+
+    let reward = Reward {
+        watcher: auth.watcher.clone(),
+        amount: WrappedBalance::from(auth.reward.clone()).into(),
+    };
+    let key = (auth.channel_id.clone(), (index as PartIdx).into());
+    REWARDS.save(deps.storage, key, &reward)?;
+    Ok(())
+}
+
+/// Pays the [REWARDS] of a channel out of the [HOLDINGS] of the rewarding
+/// participants.
+///
+/// A reward is capped by the holding so that concluding can not fail.
+fn pay_rewards(
+    storage: &mut dyn Storage,
+    mut res: Response,
+    channel_id: &ChannelId,
+    num_parts: usize,
+) -> Result<Response, ContractError> {
+    for i in 0..num_parts {
+        let key = (channel_id.clone(), (i as PartIdx).into());
+        let reward = match REWARDS.may_load(storage, key.clone())? {
+            Some(reward) => reward,
+            None => continue,
+        };
+        REWARDS.remove(storage, key.clone());
+        let holding = HOLDINGS.may_load(storage, key.clone())?.unwrap_or_default();
+        let paid: WrappedBalance = reward
+            .amount
+            .iter()
+            .filter_map(|c| {
+                let available = find_amount(&holding.0 .0, &c.denom)?;
+                Some(Coin::new(c.amount.min(available).u128(), c.denom.clone()))
+            })
+            .collect::<Vec<Coin>>()
+            .into();
+        HOLDINGS.save(storage, key, &holding.checked_sub(&paid)?)?;
+        sub_liabilities(storage, &paid)?;
+        res = send(res, &reward.watcher, &paid);
+    }
+    Ok(res)
+}
+
+/// See [crate::msg::ExecuteMsg::Checkpoint].
+fn checkpoint(
+    deps: DepsMut,
+    now: Timestamp,
+    params: &Params,
+    state: &State,
+    sigs: &[Sig],
+) -> Result<Response, ContractError> {
+    ensure!(!params.is_expired(now), ContractError::ChannelExpired {});
+    verify_state(deps.as_ref(), params, state, sigs, None)?;
+    let channel_id = &state.channel_id;
+    ensure_not_closed(deps.storage, channel_id)?;
+    ensure_checkpoint(deps.storage, channel_id, state.version)?;
+    let config = load_config(deps.storage)?;
+    validate_new_channel(deps.storage, &config, params, Some(state))?;
+    ensure!(
+        !DISPUTES.has(deps.storage, channel_id.clone()),
+        ContractError::ChannelDisputed {}
+    );
+
+    let checkpoint = RegisteredState::new(state, config.compact_disputes)?;
+    CHECKPOINTS.save(deps.storage, channel_id.clone(), &checkpoint)?;
+    let data = CheckpointResult {
+        channel_id: channel_id.clone(),
+        checkpoint: state.version,
+    };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+/// See [crate::msg::ExecuteMsg::ExtendDispute].
+fn extend_dispute(
+    deps: DepsMut,
+    now: Timestamp,
+    params: &Params,
+    extension: &DisputeExtension,
+    sigs: &[Sig],
+) -> Result<Response, ContractError> {
+    let channel_id = &extension.channel_id;
+    let mut dispute = load_running_dispute(deps.storage, params, channel_id, extension.version)?;
+    ensure!(now < dispute.timeout, ContractError::DisputeTimedOut {});
+    let config = load_config(deps.storage)?;
+    let max_timeout = now.plus_seconds(config.limits.max_dispute_duration.u64());
+    ensure!(
+        extension.timeout > dispute.timeout && extension.timeout <= max_timeout,
+        ContractError::InvalidTimeout {}
+    );
+    let signers = load_signers(deps.storage, params)?;
+    extension.verify_fully_signed(&signers, sigs, deps.api)?;
+    use_dispute_nonce(deps.storage, channel_id, extension.nonce)?;
+
+    dispute.timeout = extension.timeout;
+    DISPUTES.save(deps.storage, channel_id.clone(), &dispute)?;
+    dispute_response(channel_id.clone(), &dispute)
+}
+
+/// See [crate::msg::ExecuteMsg::CancelDispute].
+fn cancel_dispute(
+    deps: DepsMut,
+    params: &Params,
+    cancellation: &DisputeCancellation,
+    sigs: &[Sig],
+) -> Result<Response, ContractError> {
+    let channel_id = &cancellation.channel_id;
+    let dispute = load_running_dispute(deps.storage, params, channel_id, cancellation.version)?;
+    let signers = load_signers(deps.storage, params)?;
+    cancellation.verify_fully_signed(&signers, sigs, deps.api)?;
+    use_dispute_nonce(deps.storage, channel_id, cancellation.nonce)?;
+
+    DISPUTES.remove(deps.storage, channel_id.clone());
+    // Keep the registered version as floor for later disputes.
+    CHECKPOINTS.save(deps.storage, channel_id.clone(), &dispute.state)?;
+    let checkpoint = dispute.state.version;
+    let res = match BONDS.may_load(deps.storage, channel_id.clone())? {
+        Some(bond) => release_bond(deps.storage, Response::new(), channel_id, &bond.owner)?,
+        None => Response::new(),
+    };
+    let data = CancelDisputeResult {
+        channel_id: channel_id.clone(),
+        checkpoint,
+    };
+    Ok(res.set_data(to_binary(&data)?))
+}
+
+/// Loads the dispute of `params` that is not concluded yet and registered
+/// `version`.
+fn load_running_dispute(
+    storage: &dyn Storage,
+    params: &Params,
+    channel_id: &ChannelId,
+    version: Version,
+) -> Result<Dispute, ContractError> {
+    ensure!(
+        &params.channel_id()? == channel_id,
+        ContractError::WrongChannelId {}
+    );
+    ensure_not_closed(storage, channel_id)?;
+    let dispute = DISPUTES
+        .may_load(storage, channel_id.clone())?
+        .ok_or(ContractError::UnknownDispute {})?;
+    ensure!(!dispute.concluded, ContractError::AlreadyConcluded {});
+    ensure!(
+        dispute.state.version == version,
+        ContractError::WrongDisputeVersion {}
+    );
+    Ok(dispute)
+}
+
+/// Returns the [DisputeResult] of `dispute` as response data.
+fn dispute_response(channel_id: ChannelId, dispute: &Dispute) -> Result<Response, ContractError> {
+    let data = DisputeResult {
+        channel_id,
+        version: dispute.state.version,
+        timeout: dispute.timeout,
+    };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
 /// See [crate::msg::ExecuteMsg::Conclude].
 fn conclude(
     deps: DepsMut,
     now: Timestamp,
+    sender: &OnIdentity,
     params: &Params,
     state: &State,
     sigs: &[Sig],
+    arbiter_sig: Option<&Sig>,
 ) -> Result<Response, ContractError> {
-    state.verify_fully_signed(params, sigs, deps.api)?;
+    verify_state(deps.as_ref(), params, state, sigs, arbiter_sig)?;
     let channel_id = &state.channel_id;
+    ensure_not_closed(deps.storage, channel_id)?;
+    ensure_checkpoint(deps.storage, channel_id, state.version)?;
+    let config = load_config(deps.storage)?;
+    params.validate()?;
 
-    match DISPUTES.may_load(deps.storage, channel_id.clone())? {
+    let registered = match DISPUTES.may_load(deps.storage, channel_id.clone())? {
         Some(dispute) => {
             // Return if already concluded.
             if dispute.concluded {
                 // Ensure that the state equals the concluded state.
                 ensure!(
-                    state == &dispute.state,
+                    dispute.state.matches(state)?,
                     ContractError::ConcludedWithDifferentState {}
                 );
-                return Ok(Default::default());
+                let fee = WrappedBalance::default();
+                let outcome = resolve_locks(deps.storage, now, params, state)?;
+                return conclude_response(
+                    Response::new(),
+                    channel_id.clone(),
+                    &outcome,
+                    sender,
+                    &fee,
+                );
             }
 
-            // Ensure that the dispute period is over or the state is final.
+            // Ensure that no older state than the registered one is used.
             ensure!(
-                now >= dispute.timeout || state.finalized,
-                ContractError::ConcludedTooEarly {}
+                state.version >= dispute.state.version,
+                ContractError::DisputeVersionTooLow {}
             );
+            // Ensure that the dispute period is over or the state is final.
+            // Arbitrated states only need the arbiter delay to be over.
+            if arbiter_sig.is_some() {
+                ensure_arbitrable(now, params, dispute.timeout)?;
+            } else {
+                ensure!(
+                    now >= dispute.timeout || state.finalized,
+                    ContractError::ConcludedTooEarly {}
+                );
+            }
+            Some(dispute.state.version)
         }
         None => {
+            ensure!(
+                arbiter_sig.is_none(),
+                ContractError::ArbitrationWithoutDispute {}
+            );
             // Ensure that the state is final.
             ensure!(state.finalized, ContractError::StateNotFinal {});
+            None
         }
-    }
+    };
 
     // Persist the outcome.
-    push_outcome(
+    let fee = WrappedBalance::from(state.fee.clone());
+    let outcome = resolve_locks(deps.storage, now, params, state)?;
+    push_outcome(deps.storage, params, &outcome, &fee)?;
+    let n = params.participants.len();
+    let res = pay_rewards(deps.storage, Response::new(), channel_id, n)?;
+    let res = settle_bond(
         deps.storage,
+        res,
         channel_id,
-        &params.participants,
-        &state.balances,
+        sender,
+        registered,
+        state.version,
     )?;
     let reg = Dispute {
-        state: state.clone(),
+        state: RegisteredState::new(state, config.compact_disputes)?,
         timeout: Timestamp::from_seconds(0),
         concluded: true,
     };
     DISPUTES.save(deps.storage, channel_id.clone(), &reg)?;
-    Ok(Default::default())
+    let res = conclude_response(res, channel_id.clone(), &outcome, sender, &fee)?;
+    if arbiter_sig.is_some() {
+        return Ok(res.add_event(arbitrate_event("conclude", channel_id, state.version)));
+    }
+    Ok(res)
+}
+
+/// See [crate::msg::ExecuteMsg::ConcludeDispute].
+fn conclude_dispute(
+    deps: DepsMut,
+    now: Timestamp,
+    sender: &OnIdentity,
+    channel_id: &ChannelId,
+    preimage: Option<&State>,
+) -> Result<Response, ContractError> {
+    ensure_not_closed(deps.storage, channel_id)?;
+    let mut dispute = DISPUTES
+        .may_load(deps.storage, channel_id.clone())?
+        .ok_or(ContractError::UnknownDispute {})?;
+    ensure!(!dispute.concluded, ContractError::AlreadyConcluded {});
+    ensure!(now >= dispute.timeout, ContractError::ConcludedTooEarly {});
+    let params = PARAMS.load(deps.storage, channel_id.clone())?;
+    let state = dispute.state.preimage(preimage)?;
+
+    // Persist the outcome of the registered state.
+    let fee = WrappedBalance::from(state.fee.clone());
+    let outcome = resolve_locks(deps.storage, now, &params, &state)?;
+    push_outcome(deps.storage, &params, &outcome, &fee)?;
+    let n = params.participants.len();
+    let res = pay_rewards(deps.storage, Response::new(), channel_id, n)?;
+    let version = dispute.state.version;
+    let res = settle_bond(
+        deps.storage,
+        res,
+        channel_id,
+        sender,
+        Some(version),
+        version,
+    )?;
+    dispute.timeout = Timestamp::from_seconds(0);
+    dispute.concluded = true;
+    DISPUTES.save(deps.storage, channel_id.clone(), &dispute)?;
+    conclude_response(res, channel_id.clone(), &outcome, sender, &fee)
+}
+
+/// See [crate::msg::ExecuteMsg::ConcludeExpired].
+fn conclude_expired(
+    deps: DepsMut,
+    now: Timestamp,
+    sender: &OnIdentity,
+    params: &Params,
+    preimage: Option<&State>,
+) -> Result<Response, ContractError> {
+    ensure!(params.expires_at.is_some(), ContractError::NoExpiry {});
+    ensure!(params.is_expired(now), ContractError::NotExpired {});
+    let config = load_config(deps.storage)?;
+    params.validate()?;
+    let channel_id = register_channel(deps.storage, params)?;
+
+    // Use the highest state known on-chain.
+    let (state, registered) = match DISPUTES.may_load(deps.storage, channel_id.clone())? {
+        Some(dispute) => {
+            ensure!(!dispute.concluded, ContractError::AlreadyConcluded {});
+            // The dispute was opened before the expiry and can be refuted
+            // until its timeout.
+            ensure!(now >= dispute.timeout, ContractError::ConcludedTooEarly {});
+            (
+                dispute.state.preimage(preimage)?,
+                Some(dispute.state.version),
+            )
+        }
+        None => match CHECKPOINTS.may_load(deps.storage, channel_id.clone())? {
+            Some(checkpoint) => (checkpoint.preimage(preimage)?, None),
+            None => (initial_state(deps.storage, &channel_id, params)?, None),
+        },
+    };
+
+    let fee = WrappedBalance::from(state.fee.clone());
+    let outcome = resolve_locks(deps.storage, now, params, &state)?;
+    push_outcome(deps.storage, params, &outcome, &fee)?;
+    let n = params.participants.len();
+    let res = pay_rewards(deps.storage, Response::new(), &channel_id, n)?;
+    let res = settle_bond(
+        deps.storage,
+        res,
+        &channel_id,
+        sender,
+        registered,
+        state.version,
+    )?;
+    let dispute = Dispute {
+        state: RegisteredState::new(&state, config.compact_disputes)?,
+        timeout: Timestamp::from_seconds(0),
+        concluded: true,
+    };
+    DISPUTES.save(deps.storage, channel_id.clone(), &dispute)?;
+    conclude_response(res, channel_id, &outcome, sender, &fee)
+}
+
+/// Returns a final state of version zero that allocates the deposits of a
+/// registered channel back to the participants.
+fn initial_state(
+    storage: &dyn Storage,
+    channel_id: &ChannelId,
+    params: &Params,
+) -> Result<State, ContractError> {
+    let mut balances = Vec::with_capacity(params.participants.len());
+    for i in 0..params.participants.len() {
+        let key = (channel_id.clone(), (i as PartIdx).into());
+        let holding = HOLDINGS.may_load(storage, key)?.unwrap_or_default();
+        balances.push(holding.0);
+    }
+    Ok(State {
+        channel_id: channel_id.clone(),
+        version: Version::zero(),
+        balances,
+        finalized: true,
+        fee: vec![],
+        locks: vec![],
+    })
+}
+
+/// Returns the [ConcludeResult] of `outcome` as response data and pays the
+/// `fee` to `sender`.
+fn conclude_response(
+    res: Response,
+    channel_id: ChannelId,
+    outcome: &[cw0::NativeBalance],
+    sender: &OnIdentity,
+    fee: &WrappedBalance,
+) -> Result<Response, ContractError> {
+    let outcome = outcome
+        .iter()
+        .enumerate()
+        .map(|(i, bals)| Holding {
+            part: i as PartIdx,
+            amount: WrappedBalance::from(bals.0.clone()).into(),
+        })
+        .collect();
+    let data = ConcludeResult {
+        channel_id,
+        outcome,
+        fee: fee.clone().into(),
+    };
+    Ok(send(res, sender, fee).set_data(to_binary(&data)?))
+}
+
+/// See [crate::msg::ExecuteMsg::Splice].
+fn splice_channel(
+    deps: DepsMut,
+    info: &MessageInfo,
+    params: &Params,
+    splice: &Splice,
+    sigs: &[Sig],
+) -> Result<Response, ContractError> {
+    let state = &splice.state;
+    ensure!(!state.finalized, ContractError::StateFinal {});
+    let signers = load_signers(deps.storage, params)?;
+    splice.verify_fully_signed(params, &signers, sigs, deps.api)?;
+    let channel_id = state.channel_id.clone();
+    ensure_not_closed(deps.storage, &channel_id)?;
+    let config = load_config(deps.storage)?;
+    validate_new_channel(deps.storage, &config, params, Some(state))?;
+    ensure!(
+        !DISPUTES.has(deps.storage, channel_id.clone()),
+        ContractError::ChannelDisputed {}
+    );
+    // The splice state must be newer than the last splice.
+    if let Some(checkpoint) = CHECKPOINTS.may_load(deps.storage, channel_id.clone())? {
+        ensure!(
+            state.version > checkpoint.version,
+            ContractError::VersionBelowCheckpoint {}
+        );
+    }
+    let funds = splice_funds(splice)?;
+    ensure!(
+        funds == WrappedBalance::from(info.funds.clone()),
+        ContractError::SpliceFundsMismatch {}
+    );
+    ensure!(
+        funds.0.is_empty() || !config.deposits_paused,
+        ContractError::Paused {}
+    );
+    register_channel(deps.storage, params)?;
+
+    let n = params.participants.len();
+    let mut holdings = Vec::with_capacity(n);
+    for i in 0..n {
+        let key = (channel_id.clone(), (i as PartIdx).into());
+        holdings.push(HOLDINGS.may_load(deps.storage, key)?.unwrap_or_default());
+    }
+    for deposit in splice.deposits.iter() {
+        let holding = holdings
+            .get_mut(deposit.part as usize)
+            .ok_or(ContractError::WrongParticipant {})?;
+        *holding = holding.checked_add(&deposit.amount.clone().into())?;
+    }
+    let mut res = Response::new();
+    let mut paid = WrappedBalance::default();
+    for payout in splice.payouts.iter() {
+        let amount = WrappedBalance::from(payout.amount.clone());
+        let holding = holdings
+            .get_mut(payout.part as usize)
+            .ok_or(ContractError::WrongParticipant {})?;
+        *holding = holding
+            .checked_sub(&amount)
+            .map_err(|_| ContractError::InsufficientDeposits {})?;
+        paid = paid.checked_add(&amount)?;
+        res = send(res, &payout.receiver, &amount);
+    }
+    add_liabilities(deps.storage, &funds)?;
+    sub_liabilities(deps.storage, &paid)?;
+
+    // The remaining deposits must cover the splice state.
+    let mut sum_deposit = WrappedBalance::default();
+    for (i, holding) in holdings.iter().enumerate() {
+        let key = (channel_id.clone(), (i as PartIdx).into());
+        HOLDINGS.save(deps.storage, key, holding)?;
+        sum_deposit = sum_deposit.checked_add(holding)?;
+    }
+    let mut sum_state = WrappedBalance::from(state.fee.clone());
+    for bals in state.balances.iter() {
+        sum_state = sum_state.checked_add(&bals.0.clone().into())?;
+    }
+    for lock in state.locks.iter() {
+        sum_state = sum_state.checked_add(&lock.amount.clone().into())?;
+    }
+    ensure!(
+        sum_deposit.greater_or_equal(&sum_state),
+        ContractError::InsufficientDeposits {}
+    );
+    for deposit in splice.deposits.iter() {
+        let amount = WrappedBalance::from(deposit.amount.clone());
+        let holding = &holdings[deposit.part as usize];
+        let liabilities = load_liabilities(deps.storage, &amount)?;
+        enforce_deposit_limits(&config, &amount, holding, &liabilities)?;
+    }
+    let checkpoint = RegisteredState::new(state, config.compact_disputes)?;
+    CHECKPOINTS.save(deps.storage, channel_id.clone(), &checkpoint)?;
+
+    let data = SpliceResult {
+        channel_id,
+        version: state.version,
+        holdings: holdings
+            .into_iter()
+            .enumerate()
+            .map(|(i, holding)| Holding {
+                part: i as PartIdx,
+                amount: holding.into(),
+            })
+            .collect(),
+    };
+    Ok(res.set_data(to_binary(&data)?))
+}
+
+/// Returns the sum of all deposits of a [Splice].
+fn splice_funds(splice: &Splice) -> Result<WrappedBalance, ContractError> {
+    let mut funds = WrappedBalance::default();
+    for deposit in splice.deposits.iter() {
+        funds = funds.checked_add(&deposit.amount.clone().into())?;
+    }
+    Ok(funds)
+}
+
+/// See [crate::msg::ExecuteMsg::RevealPreimage].
+fn reveal_preimage(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    preimage: &Binary,
+) -> Result<Response, ContractError> {
+    let hash = hash_bytes(preimage.as_slice());
+    // Only the first reveal counts towards the lock timeouts.
+    let revealed_at = match PREIMAGES.may_load(storage, hash.clone())? {
+        Some(revealed_at) => revealed_at,
+        None => {
+            PREIMAGES.save(storage, hash.clone(), &now)?;
+            now
+        }
+    };
+    let data = RevealResult { hash, revealed_at };
+    Ok(Response::new().set_data(to_binary(&data)?))
 }
 
 /// See [crate::msg::ExecuteMsg::Withdraw].
 fn withdraw(
     deps: DepsMut,
+    sender: &OnIdentity,
     withdrawal: &Withdrawal,
     withdrawal_sig: &Sig,
 ) -> Result<Response, ContractError> {
-    withdrawal.verify(withdrawal_sig, deps.api)?;
+    let from = load_authority(deps.storage, &withdrawal.channel_id, &withdrawal.part)?;
+    withdrawal.verify(withdrawal_sig, &from, deps.api)?;
+    ensure_not_closed(deps.storage, &withdrawal.channel_id)?;
     // Load the dispute.
     match DISPUTES.may_load(deps.storage, withdrawal.channel_id.clone())? {
         None => Err(ContractError::UnknownChannel {}),
@@ -226,66 +1413,432 @@ fn withdraw(
             } else {
                 let funding_id = withdrawal.funding_id()?;
                 // Load the deposit.
-                let deposit = DEPOSITS.may_load(deps.storage, funding_id.clone())?;
+                let deposit = load_deposit(deps.storage, &funding_id)?;
                 ensure!(deposit.is_some(), ContractError::UnknownDeposit {});
                 let deposit = deposit.unwrap();
                 // Remove the deposit.
-                DEPOSITS.remove(deps.storage, funding_id);
+                remove_deposit(deps.storage, &funding_id)?;
+                mark_withdrawn(deps.storage, &funding_id)?;
+                sub_liabilities(deps.storage, &deposit)?;
                 // Transfer the outcome to the user.
-                let transfer = Send {
-                    to_address: withdrawal.receiver.clone().into_string(),
-                    amount: deposit.into(),
-                };
-                Ok(Response::new().add_message(transfer))
+                let (res, data) = pay_withdrawal(Response::new(), sender, withdrawal, &deposit)?;
+                Ok(res.set_data(to_binary(&data)?))
             }
         }
     }
 }
 
-/// Pushes the outcome of a channel back into the `DEPOSITS` map.
+/// See [crate::msg::ExecuteMsg::Novate].
+fn novate(
+    deps: DepsMut,
+    params: &Params,
+    novation: &Novation,
+    sigs: &[Sig],
+) -> Result<Response, ContractError> {
+    let channel_id = params.channel_id()?;
+    ensure!(
+        novation.channel_id == channel_id,
+        ContractError::WrongChannelId {}
+    );
+    ensure_not_closed(deps.storage, &channel_id)?;
+    let config = load_config(deps.storage)?;
+    validate_new_channel(deps.storage, &config, params, None)?;
+    let signers = load_signers(deps.storage, params)?;
+    ensure!(
+        signers.get(novation.part as usize) == Some(&novation.old),
+        ContractError::WrongParticipant {}
+    );
+    ensure!(
+        novation.new.is_well_formed(),
+        ContractError::InvalidIdentity {}
+    );
+    ensure!(
+        !signers.contains(&novation.new),
+        ContractError::DuplicateParticipant {}
+    );
+    novation.verify_fully_signed(&signers, sigs, deps.api)?;
+
+    use_novation_nonce(deps.storage, &channel_id, novation.part, novation.nonce)?;
+
+    register_channel(deps.storage, params)?;
+    let key = (channel_id.clone(), novation.part.into());
+    AUTHORITIES.save(deps.storage, key, &novation.new)?;
+    let data = NovationResult {
+        channel_id,
+        part: novation.part,
+        identity: novation.new.clone(),
+    };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+/// See [crate::msg::ExecuteMsg::Rollover].
+fn rollover(
+    deps: DepsMut,
+    old_channel: &ChannelId,
+    new_params: &Params,
+    sigs: &[Sig],
+) -> Result<Response, ContractError> {
+    ensure_not_closed(deps.storage, old_channel)?;
+    let dispute = DISPUTES
+        .may_load(deps.storage, old_channel.clone())?
+        .ok_or(ContractError::UnknownChannel {})?;
+    ensure!(dispute.concluded, ContractError::NotConcluded {});
+    let old_params = PARAMS.load(deps.storage, old_channel.clone())?;
+    let new_channel = new_params.channel_id()?;
+    ensure!(
+        &new_channel != old_channel,
+        ContractError::WrongChannelId {}
+    );
+    let rollover = Rollover {
+        old_channel: old_channel.clone(),
+        new_channel: new_channel.clone(),
+    };
+    let signers = load_signers(deps.storage, &old_params)?;
+    rollover.verify_fully_signed(&signers, sigs, deps.api)?;
+    ensure_not_closed(deps.storage, &new_channel)?;
+    let config = load_config(deps.storage)?;
+    ensure!(!config.deposits_paused, ContractError::Paused {});
+    validate_new_channel(deps.storage, &config, new_params, None)?;
+
+    let mut deposits = vec![];
+    for (part, signer) in old_params.participants.iter().zip(signers.iter()) {
+        let old_fid = calc_funding_id(old_channel, part)?;
+        // Skip participants that already withdrew.
+        let holding = match load_deposit(deps.storage, &old_fid)? {
+            Some(holding) => holding,
+            None => continue,
+        };
+        // Holdings follow the current identity of a participant.
+        ensure!(
+            new_params.participants.contains(signer),
+            ContractError::WrongParticipant {}
+        );
+        remove_deposit(deps.storage, &old_fid)?;
+        mark_withdrawn(deps.storage, &old_fid)?;
+
+        // The liabilities stay the same since the funds stay in the contract.
+        let funding_id = calc_funding_id(&new_channel, signer)?;
+        let new_holding = load_deposit(deps.storage, &funding_id)?
+            .unwrap_or_default()
+            .checked_add(&holding)?;
+        save_deposit(deps.storage, &funding_id, &new_holding)?;
+        let liabilities = load_liabilities(deps.storage, &holding)?;
+        enforce_deposit_limits(&config, &holding, &new_holding, &liabilities)?;
+        deposits.push(DepositResult {
+            funding_id,
+            holding: new_holding.into(),
+        });
+    }
+    let data = RolloverResult {
+        channel_id: new_channel,
+        deposits,
+    };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+/// See [crate::msg::ExecuteMsg::Settle].
+fn settle(
+    deps: DepsMut,
+    now: Timestamp,
+    sender: &OnIdentity,
+    params: &Params,
+    state: &State,
+    sigs: &[Sig],
+    withdrawals: Vec<SignedWithdrawal>,
+) -> Result<Response, ContractError> {
+    ensure!(state.finalized, ContractError::StateNotFinal {});
+    let signers = load_signers(deps.storage, params)?;
+    state.verify_fully_signed(params, &signers, sigs, deps.api)?;
+    let channel_id = state.channel_id.clone();
+    ensure_not_closed(deps.storage, &channel_id)?;
+    ensure_checkpoint(deps.storage, &channel_id, state.version)?;
+    params.validate()?;
+    // A running dispute can be settled with a newer final state.
+    let dispute = DISPUTES.may_load(deps.storage, channel_id.clone())?;
+    if let Some(dispute) = &dispute {
+        ensure!(!dispute.concluded, ContractError::AlreadyConcluded {});
+        ensure!(
+            state.version >= dispute.state.version,
+            ContractError::DisputeVersionTooLow {}
+        );
+    }
+    // Every participant must authorize its payout.
+    ensure!(
+        withdrawals.len() == params.participants.len(),
+        ContractError::WrongWithdrawalNum {}
+    );
+    for (i, SignedWithdrawal { withdrawal, sig }) in withdrawals.iter().enumerate() {
+        ensure!(
+            withdrawal.channel_id == channel_id,
+            ContractError::WrongChannelId {}
+        );
+        ensure!(
+            withdrawal.part == params.participants[i],
+            ContractError::WrongParticipant {}
+        );
+        withdrawal.verify(sig, &signers[i], deps.api)?;
+    }
+
+    let fee = WrappedBalance::from(state.fee.clone());
+    let outcome = resolve_locks(deps.storage, now, params, state)?;
+    push_outcome(deps.storage, params, &outcome, &fee)?;
+    let n = params.participants.len();
+    let res = pay_rewards(deps.storage, Response::new(), &channel_id, n)?;
+    let registered = dispute.map(|dispute| dispute.state.version);
+    let res = settle_bond(
+        deps.storage,
+        res,
+        &channel_id,
+        sender,
+        registered,
+        state.version,
+    )?;
+    let mut res = send(res, sender, &fee);
+    let mut payouts = vec![];
+    for (i, SignedWithdrawal { withdrawal, .. }) in withdrawals.iter().enumerate() {
+        let key = (channel_id.clone(), (i as PartIdx).into());
+        let holding = HOLDINGS.load(deps.storage, key)?;
+        sub_liabilities(deps.storage, &holding)?;
+        let (next, payout) = pay_withdrawal(res, sender, withdrawal, &holding)?;
+        res = next;
+        payouts.push(payout);
+    }
+    close_channel(
+        deps.storage,
+        &channel_id,
+        state.version,
+        params.participants.len(),
+    )?;
+    let data = SettleResult {
+        channel_id,
+        payouts,
+    };
+    Ok(res.set_data(to_binary(&data)?))
+}
+
+/// See [crate::msg::ExecuteMsg::SetPaused].
+fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    deposits: bool,
+    disputes: bool,
+) -> Result<Response, ContractError> {
+    let mut config = load_admin_config(deps.storage, &info)?;
+    config.deposits_paused = deposits;
+    config.disputes_paused = disputes;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().set_data(to_binary(&ConfigResponse(config))?))
+}
+
+/// See [crate::msg::ExecuteMsg::TransferAdmin] and
+/// [crate::msg::ExecuteMsg::RenounceAdmin].
+fn set_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Option<OnIdentity>,
+) -> Result<Response, ContractError> {
+    let mut config = load_admin_config(deps.storage, &info)?;
+    config.admin = admin;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().set_data(to_binary(&ConfigResponse(config))?))
+}
+
+/// See [crate::msg::ExecuteMsg::UpdateConfig].
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    allowed_denoms: Option<Vec<String>>,
+    funding_caps: Vec<Coin>,
+    denom_caps: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    let mut config = load_admin_config(deps.storage, &info)?;
+    config.allowed_denoms = allowed_denoms;
+    config.funding_caps = funding_caps;
+    config.denom_caps = denom_caps;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().set_data(to_binary(&ConfigResponse(config))?))
+}
+
+/// See [crate::msg::ExecuteMsg::SetChannelLimits].
+fn set_channel_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    limits: ChannelLimits,
+) -> Result<Response, ContractError> {
+    let mut config = load_admin_config(deps.storage, &info)?;
+    limits.validate()?;
+    config.limits = limits;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().set_data(to_binary(&ConfigResponse(config))?))
+}
+
+/// See [crate::msg::ExecuteMsg::SetDisputeBond].
+fn set_dispute_bond(
+    deps: DepsMut,
+    info: MessageInfo,
+    dispute_bond: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    let mut config = load_admin_config(deps.storage, &info)?;
+    config.dispute_bond = dispute_bond;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new().set_data(to_binary(&ConfigResponse(config))?))
+}
+
+/// Loads the [Config] and ensures that the sender is the admin.
+fn load_admin_config(storage: &dyn Storage, info: &MessageInfo) -> Result<Config, ContractError> {
+    let config = load_config(storage)?;
+    ensure!(
+        config.admin.as_ref() == Some(&info.sender),
+        ContractError::Unauthorized {}
+    );
+    Ok(config)
+}
+
+/// Returns the balances of `state` with all its [State::locks] resolved.
 ///
-/// Checks that the sum of outcome is smaller or equal to the sum
+/// A lock goes to its receiver if its preimage was revealed before the
+/// lock timeout and back to its sender otherwise.
+/// Fails with [ContractError::LockPending] while a lock can still be
+/// claimed by its receiver.
+fn resolve_locks(
+    storage: &dyn Storage,
+    now: Timestamp,
+    params: &Params,
+    state: &State,
+) -> Result<Vec<cw0::NativeBalance>, ContractError> {
+    let mut outcome: Vec<WrappedBalance> = state
+        .balances
+        .iter()
+        .map(|bals| WrappedBalance::from(bals.0.clone()))
+        .collect();
+    let n = params.participants.len();
+    for lock in state.locks.iter() {
+        let (from, to) = (lock.sender as usize, lock.receiver as usize);
+        ensure!(
+            from < n && to < n && from != to && n == outcome.len(),
+            ContractError::InvalidLock {}
+        );
+        let revealed = PREIMAGES
+            .may_load(storage, lock.hash.clone())?
+            .is_some_and(|revealed_at| revealed_at < lock.timeout);
+        ensure!(
+            revealed || now >= lock.timeout,
+            ContractError::LockPending {}
+        );
+        let part = if revealed { to } else { from };
+        outcome[part] = outcome[part]
+            .checked_add(&lock.amount.clone().into())
+            .map_err(outcome_overflow)?;
+    }
+    Ok(outcome.into_iter().map(|bals| bals.0).collect())
+}
+
+/// Pushes the outcome of a channel back into the [HOLDINGS] map.
+///
+/// Registers the channel first, see [register_channel].
+/// Checks that the sum of outcome and `fee` is smaller or equal to the sum
 /// of deposits in the channel.
 /// This ensures that the participants cannot withdraw more than they
 /// initially deposited.
+/// The caller has to pay out the `fee`.
 fn push_outcome(
     storage: &mut dyn Storage,
-    channel_id: &ChannelId,
-    parts: &[OffIdentity],
+    params: &Params,
     outcome: &[cw0::NativeBalance],
-) -> Result<Response, ContractError> {
+    fee: &WrappedBalance,
+) -> Result<(), ContractError> {
     ensure!(
-        parts.len() == outcome.len(),
+        params.participants.len() == outcome.len(),
         ContractError::InvalidOutcome {}
     );
-    ensure!(!parts.is_empty(), ContractError::InvalidOutcome {});
+    ensure!(!outcome.is_empty(), ContractError::InvalidOutcome {});
+    let channel_id = register_channel(storage, params)?;
 
-    // Save all Funding IDs for later.
-    let mut fids = Vec::<FundingId>::default();
     // Calculate the sums of the outcome and deposit.
     let mut sum_outcome = WrappedBalance::default();
     let mut sum_deposit = WrappedBalance::default();
 
-    for (i, part) in parts.iter().enumerate() {
-        let fid = calc_funding_id(channel_id, part)?;
-        fids.push(fid.clone());
-        let deposit = DEPOSITS.load(storage, fid).unwrap_or_default();
+    for (i, outcome_) in outcome.iter().enumerate() {
+        let key = (channel_id.clone(), (i as PartIdx).into());
+        let deposit = HOLDINGS.may_load(storage, key)?.unwrap_or_default();
 
-        let outcome_ = WrappedBalance::from(outcome[i].0.clone());
-        sum_outcome = sum_outcome.add(&outcome_);
-        sum_deposit = sum_deposit.add(&deposit);
+        let outcome_ = WrappedBalance::from(outcome_.0.clone());
+        sum_outcome = sum_outcome
+            .checked_add(&outcome_)
+            .map_err(outcome_overflow)?;
+        sum_deposit = sum_deposit.checked_add(&deposit)?;
     }
+    let sum_paid = sum_outcome.checked_add(fee).map_err(outcome_overflow)?;
     // Ensure that the participants of a channel can never withdraw more
     // than their initially deposited.
+    // Outcomes can only name denoms that are allowed or were deposited.
+    let config = load_config(storage)?;
+    for coin in sum_paid.0 .0.iter() {
+        ensure!(
+            config.allows_denom(&coin.denom)
+                || find_amount(&sum_deposit.0 .0, &coin.denom).is_some(),
+            ContractError::DenomNotAllowed {
+                denom: coin.denom.clone()
+            }
+        );
+    }
     ensure!(
-        sum_deposit.greater_or_equal(&sum_outcome),
+        sum_deposit.greater_or_equal(&sum_paid),
         ContractError::InsufficientDeposits {}
     );
     // Over-funding a channel will result in lost funds.
     // Now we split up all funds according to the outcome.
-    for (i, fid) in fids.iter().enumerate() {
-        let outcome_ = WrappedBalance::from(outcome[i].0.clone());
-        DEPOSITS.save(storage, fid.clone(), &outcome_)?;
+    for (i, outcome_) in outcome.iter().enumerate() {
+        let key = (channel_id.clone(), (i as PartIdx).into());
+        HOLDINGS.save(storage, key, &WrappedBalance::from(outcome_.0.clone()))?;
     }
-    Ok(Default::default())
+    // The contract only owes the outcome from now on.
+    sub_liabilities(storage, &sum_deposit)?;
+    add_liabilities(storage, &sum_outcome)?;
+    Ok(())
+}
+
+/// Reports a [ContractError::BalanceOverflow] of an outcome as
+/// [ContractError::OutcomeOverflow].
+fn outcome_overflow(err: ContractError) -> ContractError {
+    match err {
+        ContractError::BalanceOverflow { denom } => ContractError::OutcomeOverflow { denom },
+        err => err,
+    }
+}
+
+/// Adds a transfer of `amount` to `to`.
+///
+/// Sending nothing is rejected by the bank module and therefore skipped.
+fn send(res: Response, to: &OnIdentity, amount: &WrappedBalance) -> Response {
+    if amount.0.is_empty() {
+        return res;
+    }
+    res.add_message(Send {
+        to_address: to.to_string(),
+        amount: amount.clone().into(),
+    })
+}
+
+/// Pays out `holding` according to `withdrawal`.
+///
+/// The receiver is credited with the holding minus the [Withdrawal::fee],
+/// which goes to `sender`.
+fn pay_withdrawal(
+    res: Response,
+    sender: &OnIdentity,
+    withdrawal: &Withdrawal,
+    holding: &WrappedBalance,
+) -> Result<(Response, WithdrawResult), ContractError> {
+    let fee = WrappedBalance::from(withdrawal.fee.clone());
+    let amount = holding.checked_sub(&fee)?;
+    let res = send(res, &withdrawal.receiver, &amount);
+    let res = send(res, sender, &fee);
+    let result = WithdrawResult {
+        funding_id: withdrawal.funding_id()?,
+        receiver: withdrawal.receiver.clone(),
+        amount: amount.into(),
+        fee: fee.into(),
+    };
+    Ok((res, result))
 }
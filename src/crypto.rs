@@ -30,6 +30,19 @@ pub struct Sig(pub WrappedBinary);
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OffIdentity(pub WrappedBinary);
 
+impl OffIdentity {
+    /// Returns whether this is a SEC1 encoded secp256k1 public key.
+    ///
+    /// Only checks the length and tag, not whether the point is on the curve.
+    pub fn is_well_formed(&self) -> bool {
+        match self.0.as_slice() {
+            [0x02 | 0x03, rest @ ..] => rest.len() == 32,
+            [0x04, rest @ ..] => rest.len() == 64,
+            _ => false,
+        }
+    }
+}
+
 /// On-Chain identity of a participant.
 pub type OnIdentity = cosmwasm_std::Addr;
 /// Cryptographic hash.
@@ -56,6 +69,11 @@ pub fn hash<T: Serialize>(obj: &T, prefix: Vec<u8>) -> Result<Hasher, ContractEr
     Ok(Hasher::new().chain(&data))
 }
 
+/// Returns the digest of raw bytes, as used by [crate::types::Lock::hash].
+pub fn hash_bytes(data: &[u8]) -> Hash {
+    Hasher::digest(data).as_slice().into()
+}
+
 /// Verify a signature on a `Serialize` object.
 ///
 /// All validation is done by this method to allow easy swapping of
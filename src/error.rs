@@ -72,8 +72,14 @@ pub enum ContractError {
     #[error("Invalid outcome")]
     InvalidOutcome {},
 
-    #[error("Outcome overflow")]
-    OutcomeOverflow {},
+    #[error("Outcome overflow: {denom}")]
+    OutcomeOverflow { denom: String },
+
+    #[error("Balance overflow: {denom}")]
+    BalanceOverflow { denom: String },
+
+    #[error("Balance underflow: {denom}")]
+    BalanceUnderflow { denom: String },
 
     #[error("Demons mismatch")]
     DenomMismatch {},
@@ -87,6 +93,114 @@ pub enum ContractError {
     #[error("Not concluded")]
     NotConcluded {},
 
+    #[error("Missing preimage of the registered state")]
+    MissingPreimage {},
+
+    #[error("Wrong preimage of the registered state")]
+    WrongPreimage {},
+
+    #[error("Channel closed")]
+    ChannelClosed {},
+
+    #[error("Denom not allowed: {denom}")]
+    DenomNotAllowed { denom: String },
+
+    #[error("Funding cap exceeded: {denom}")]
+    FundingCapExceeded { denom: String },
+
+    #[error("Denom cap exceeded: {denom}")]
+    DenomCapExceeded { denom: String },
+
+    #[error("Invalid funding id")]
+    InvalidFundingId {},
+
+    #[error("Invalid nonce")]
+    InvalidNonce {},
+
+    #[error("Invalid number of participants")]
+    InvalidParticipantNum {},
+
+    #[error("Duplicate participant")]
+    DuplicateParticipant {},
+
+    #[error("Invalid dispute duration")]
+    InvalidDisputeDuration {},
+
+    #[error("Invalid arbiter delay")]
+    InvalidArbiterDelay {},
+
+    #[error("Too many denoms")]
+    TooManyDenoms {},
+
+    #[error("Invalid channel limits")]
+    InvalidLimits {},
+
+    #[error("Wrong number of withdrawals")]
+    WrongWithdrawalNum {},
+
+    #[error("Wrong participant")]
+    WrongParticipant {},
+
+    #[error("Channel has no arbiter")]
+    ArbiterNotSet {},
+
+    #[error("Wrong arbiter signature")]
+    WrongArbiterSignature {},
+
+    #[error("Arbitration needs an open dispute")]
+    ArbitrationWithoutDispute {},
+
+    #[error("Arbitration too early")]
+    ArbitrationTooEarly {},
+
+    #[error("Version below checkpoint")]
+    VersionBelowCheckpoint {},
+
+    #[error("Channel is disputed")]
+    ChannelDisputed {},
+
+    #[error("Attached funds do not match the splice deposits")]
+    SpliceFundsMismatch {},
+
+    #[error("Wrong dispute version")]
+    WrongDisputeVersion {},
+
+    #[error("Invalid timeout")]
+    InvalidTimeout {},
+
+    #[error("Nonce already used")]
+    NonceUsed {},
+
+    #[error("Channel does not expire")]
+    NoExpiry {},
+
+    #[error("Channel not expired")]
+    NotExpired {},
+
+    #[error("Channel expired")]
+    ChannelExpired {},
+
+    #[error("Invalid lock")]
+    InvalidLock {},
+
+    #[error("Lock pending")]
+    LockPending {},
+
+    #[error("Attached funds do not match the dispute bond")]
+    WrongBond {},
+
+    #[error("Invalid watcher")]
+    InvalidWatcher {},
+
+    #[error("Watcher authorization expired")]
+    WatcherAuthExpired {},
+
+    #[error("Batch allocation does not match the attached funds")]
+    BatchFundsMismatch {},
+
+    #[error("Paused")]
+    Paused {},
+
     #[error("Unauthorized")]
     Unauthorized {},
 }
@@ -14,20 +14,67 @@
 
 //! Messages for interacting with the [crate::contract].
 use crate::{
-    crypto::Sig,
-    types::{ChannelId, Dispute, FundingId, Params, State, Withdrawal},
+    crypto::{Hash, OffIdentity, OnIdentity, Sig},
+    types::{
+        ChannelId, ChannelLimits, Config, Dispute, DisputeCancellation, DisputeExtension,
+        FundingId, Novation, Params, PartIdx, Seconds, Splice, State, Version, WatcherAuth,
+        Withdrawal,
+    },
 };
+use cosmwasm_std::{Binary, Coin, Timestamp, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Message to initialize the [crate::contract].
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct InitMsg {
+    /// See [crate::types::Config::compact_disputes].
+    #[serde(default)]
+    pub compact_disputes: bool,
+    /// See [crate::types::Config::admin].
+    pub admin: Option<String>,
+    /// See [crate::types::Config::allowed_denoms].
+    pub allowed_denoms: Option<Vec<String>>,
+    /// See [crate::types::Config::funding_caps].
+    #[serde(default)]
+    pub funding_caps: Vec<Coin>,
+    /// See [crate::types::Config::denom_caps].
+    #[serde(default)]
+    pub denom_caps: Vec<Coin>,
+    /// See [crate::types::Config::limits].
+    #[serde(default)]
+    pub limits: ChannelLimits,
+    /// See [crate::types::Config::dispute_bond].
+    #[serde(default)]
+    pub dispute_bond: Vec<Coin>,
+}
+
+/// Message to migrate the [crate::contract] from an older version.
+///
+/// Converts all stored disputes to the current layout.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct InitMsg {}
+pub struct MigrateMsg {
+    /// See [crate::types::Config::compact_disputes].
+    ///
+    /// Enabling it drops the states of existing disputes, disabling it
+    /// cannot restore them.
+    #[serde(default)]
+    pub compact_disputes: bool,
+    /// Channels to register.
+    ///
+    /// Moves their deposits from the old funding id layout into the
+    /// per-channel layout. Channels are otherwise registered on their first
+    /// dispute or conclusion.
+    #[serde(default)]
+    pub channels: Vec<Params>,
+}
 
 /// Message to call functions on the [crate::contract].
 ///
 /// Each message corresponds to one function.
+/// The admin messages return the updated [Config] as [ConfigResponse] data.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -36,7 +83,23 @@ pub enum ExecuteMsg {
     /// Adds the newly deposited amount to already existing deposits.
     /// Funds that are deposited to an invalid `funding_id` will be lost.
     /// Over-funding a channel can result in lost funds as well.
+    ///
+    /// Returns a [DepositResult] as data.
     Deposit(FundingId),
+    /// Deposits funds that arrived through an ICS-20 transfer.
+    ///
+    /// Called by the IBC hooks middleware with the received vouchers as
+    /// funds, see the *Cross-chain deposits* section of the README.
+    /// `funding_id` is the base64 encoded [FundingId] from the memo.
+    /// Instead of failing, the funds are sent to `refund_to` if the
+    /// `funding_id` can not be parsed or the deposit is rejected.
+    /// Fails only if `refund_to` is not a valid address, in which case the
+    /// transfer is refunded on the sending chain.
+    /// Returns an [IbcDepositResult] as data.
+    IbcDeposit {
+        funding_id: String,
+        refund_to: String,
+    },
     /// Disputes a channel in case of a dishonest participant.
     ///
     /// Can only be called with a non-finalized state that is signed by
@@ -46,25 +109,330 @@ pub enum ExecuteMsg {
     /// A dispute automatically starts a timeout of [Params::dispute_duration]
     /// and can only be re-disputed while it did not run out.
     /// [ExecuteMsg::Conclude] can be called after the timeout ran out.
+    /// Opening a dispute requires the dispute bond as funds, see
+    /// [Params::bond]. The bond goes to whoever refutes the dispute with a
+    /// higher version and is returned at conclusion otherwise.
+    /// A `watcher` that refutes a dispute with a higher version earns the
+    /// reward of its [WatcherAuth], which is paid out of the outcome of the
+    /// authorizing participant once the channel is concluded.
+    /// An open dispute can also be refuted with a state signed by the
+    /// [crate::types::Arbiter] once its delay is over.
+    /// Returns a [DisputeResult] as data.
     Dispute {
         params: Params,
         state: State,
         sigs: Vec<Sig>,
+        /// Signature of the [crate::types::Arbiter] in place of `sigs`.
+        #[serde(default)]
+        arbiter_sig: Option<Sig>,
+        #[serde(default)]
+        watcher: Option<SignedWatcherAuth>,
     },
     /// Concludes a channel.
     ///
     /// Expects either a final state or the dispute period to be over.
+    /// The state must have at least the version of a registered dispute.
+    /// A state signed by the [crate::types::Arbiter] concludes an open
+    /// dispute as soon as the arbiter delay is over.
+    /// Returns a [ConcludeResult] as data.
     Conclude {
         params: Params,
         state: State,
         sigs: Vec<Sig>,
+        /// Signature of the [crate::types::Arbiter] in place of `sigs`.
+        #[serde(default)]
+        arbiter_sig: Option<Sig>,
+    },
+    /// Concludes a disputed channel with its registered state.
+    ///
+    /// Can be called by anyone after the dispute timeout ran out.
+    /// Needs no signatures since the registered state was already verified
+    /// by [ExecuteMsg::Dispute].
+    /// `state` is only needed for disputes that are stored compactly and
+    /// must then be the registered state.
+    /// Returns a [ConcludeResult] as data.
+    ConcludeDispute {
+        channel_id: ChannelId,
+        state: Option<State>,
+    },
+    /// Anchors a fully signed state on-chain without starting a dispute.
+    ///
+    /// Its version becomes the checkpoint of the channel, below which
+    /// states can not be disputed or concluded anymore. Checkpoints only
+    /// move forward and are rejected while the channel is disputed.
+    /// Returns a [CheckpointResult] as data.
+    Checkpoint {
+        params: Params,
+        state: State,
+        sigs: Vec<Sig>,
+    },
+    /// Pushes the timeout of a running dispute out.
+    ///
+    /// `sigs` are the signatures of all participants on the
+    /// [DisputeExtension]. The new timeout must be later than the current
+    /// one and at most [crate::types::ChannelLimits::max_dispute_duration]
+    /// ahead. The dispute must not have timed out yet and the nonce of the
+    /// extension must not have been used, see
+    /// [crate::types::DisputeExtension::nonce].
+    /// Returns a [DisputeResult] as data.
+    ExtendDispute {
+        params: Params,
+        extension: DisputeExtension,
+        sigs: Vec<Sig>,
+    },
+    /// Cancels a running dispute and returns the channel to
+    /// [ChannelPhase::Open].
+    ///
+    /// `sigs` are the signatures of all participants on the
+    /// [DisputeCancellation]. The version of the registered state becomes
+    /// the checkpoint of the channel, below which states can not be used
+    /// anymore. A dispute bond is returned to its owner. The nonce of the
+    /// cancellation must not have been used, like for
+    /// [ExecuteMsg::ExtendDispute].
+    /// Returns a [CancelDisputeResult] as data.
+    CancelDispute {
+        params: Params,
+        cancellation: DisputeCancellation,
+        sigs: Vec<Sig>,
+    },
+    /// Concludes a channel after its [Params::expires_at].
+    ///
+    /// Can be called by anyone and needs no signatures. The channel is
+    /// concluded with its registered state once the dispute timed out, or
+    /// with its checkpointed state otherwise. Channels with neither are
+    /// concluded with their deposits as outcome. After the expiry no new
+    /// disputes or checkpoints are accepted, but running disputes can still
+    /// be refuted.
+    /// `state` is only needed for states that are stored compactly, see
+    /// [ExecuteMsg::ConcludeDispute].
+    /// Returns a [ConcludeResult] as data.
+    ConcludeExpired {
+        params: Params,
+        state: Option<State>,
     },
     /// Withdraws funds from a concluded channel.
     ///
     /// Can be called by each participant after a channel was concluded to
     /// withdraw his outcome of the channel.
     /// This is the counterpart to [ExecuteMsg::Deposit].
+    /// Returns a [WithdrawResult] as data.
+    Withdraw { withdrawal: Withdrawal, sig: Sig },
+    /// Moves the outcome of a concluded channel into a new channel.
+    ///
+    /// `sigs` are the signatures of all participants of the old channel on
+    /// the [crate::types::Rollover], in [Params::participants] order.
+    /// Every participant of the old channel that did not withdraw yet must
+    /// take part in the new channel with its current identity, see
+    /// [ExecuteMsg::Novate]. Their holdings are credited to their funding
+    /// ids in the new channel, like with [ExecuteMsg::Withdraw] followed by
+    /// [ExecuteMsg::Deposit]. Participants that already withdrew are
+    /// skipped.
+    /// Returns a [RolloverResult] as data.
+    Rollover {
+        old_channel: ChannelId,
+        new_params: Params,
+        sigs: Vec<Sig>,
+    },
+    /// Replaces the off-chain identity of a participant.
+    ///
+    /// `sigs` are the signatures of all participants on the
+    /// [crate::types::Novation], by their current identities.
+    /// The new identity signs states, withdrawals and watcher authorizations
+    /// on behalf of the participant from then on, while its funding id stays
+    /// the same. The nonce of the novation must not have been used, see
+    /// [crate::types::Novation::nonce].
+    /// Returns a [NovationResult] as data.
+    Novate {
+        params: Params,
+        novation: Novation,
+        sigs: Vec<Sig>,
+    },
+    /// Concludes a channel with a final state and pays out all participants.
+    ///
+    /// Needs one [SignedWithdrawal] per participant in
+    /// [Params::participants] order.
+    /// The channel is closed right away, like a channel where every
+    /// participant called [ExecuteMsg::Withdraw], without leaving a
+    /// [Dispute] in storage.
+    /// Returns a [SettleResult] as data.
+    Settle {
+        params: Params,
+        state: State,
+        sigs: Vec<Sig>,
+        withdrawals: Vec<SignedWithdrawal>,
+    },
+    /// Adds funds to and pays funds out of an open channel.
+    ///
+    /// `sigs` are the signatures of all participants on the [Splice].
+    /// The deposits of the splice must be attached as funds.
+    /// The channel must not be disputed and the splice state must be newer
+    /// than the last splice. Its version becomes the checkpoint of the
+    /// channel, below which states can not be disputed or concluded.
+    /// Returns a [SpliceResult] as data.
+    Splice {
+        params: Params,
+        splice: Splice,
+        sigs: Vec<Sig>,
+    },
+    /// Reveals the preimage of a [crate::types::Lock] hash.
+    ///
+    /// Only the first reveal of a preimage is recorded.
+    /// Returns a [RevealResult] as data.
+    RevealPreimage { preimage: Binary },
+    /// Pauses or resumes deposits and disputes.
+    ///
+    /// Can only be called by the admin.
+    /// Concluding, withdrawing and refuting running disputes is always
+    /// possible so that users can exit their channels.
+    SetPaused { deposits: bool, disputes: bool },
+    /// Transfers the admin role to another account.
+    ///
+    /// Can only be called by the admin.
+    TransferAdmin { admin: String },
+    /// Removes the admin, which can not be undone.
+    ///
+    /// Can only be called by the admin.
+    RenounceAdmin {},
+    /// Replaces the deposit limits of the [Config].
+    ///
+    /// Can only be called by the admin.
+    /// All three fields are replaced, `allowed_denoms: None` allows all
+    /// denoms. Only affects future deposits and conclusions.
+    UpdateConfig {
+        allowed_denoms: Option<Vec<String>>,
+        funding_caps: Vec<Coin>,
+        denom_caps: Vec<Coin>,
+    },
+    /// Replaces the [crate::types::Config::limits].
+    ///
+    /// Can only be called by the admin.
+    /// Only applies to channels that are registered afterwards.
+    SetChannelLimits { limits: ChannelLimits },
+    /// Replaces the [crate::types::Config::dispute_bond].
+    ///
+    /// Can only be called by the admin.
+    /// Only applies to disputes that are opened afterwards.
+    SetDisputeBond { dispute_bond: Vec<Coin> },
+    /// Runs several operations atomically and in order.
+    ///
+    /// The attached funds must exactly match the sum of all
+    /// [Operation::Deposit] amounts, [Operation::Dispute] bonds and
+    /// [Operation::Splice] deposits.
+    /// Fails if any operation fails.
+    /// Returns a [BatchResult] as data.
+    Batch(Vec<Operation>),
+}
+
+/// Single operation of an [ExecuteMsg::Batch].
+///
+/// Each operation behaves like the [ExecuteMsg] of the same name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// See [ExecuteMsg::Deposit].
+    ///
+    /// `amount` is taken from the funds that are attached to the batch.
+    Deposit {
+        funding_id: FundingId,
+        amount: Vec<Coin>,
+    },
+    /// See [ExecuteMsg::Dispute].
+    ///
+    /// `bond` is taken from the funds that are attached to the batch.
+    Dispute {
+        params: Params,
+        state: State,
+        sigs: Vec<Sig>,
+        /// Signature of the [crate::types::Arbiter] in place of `sigs`.
+        #[serde(default)]
+        arbiter_sig: Option<Sig>,
+        #[serde(default)]
+        watcher: Option<SignedWatcherAuth>,
+        #[serde(default)]
+        bond: Vec<Coin>,
+    },
+    /// See [ExecuteMsg::Conclude].
+    Conclude {
+        params: Params,
+        state: State,
+        sigs: Vec<Sig>,
+        /// Signature of the [crate::types::Arbiter] in place of `sigs`.
+        #[serde(default)]
+        arbiter_sig: Option<Sig>,
+    },
+    /// See [ExecuteMsg::ConcludeDispute].
+    ConcludeDispute {
+        channel_id: ChannelId,
+        state: Option<State>,
+    },
+    /// See [ExecuteMsg::ConcludeExpired].
+    ConcludeExpired {
+        params: Params,
+        state: Option<State>,
+    },
+    /// See [ExecuteMsg::Checkpoint].
+    Checkpoint {
+        params: Params,
+        state: State,
+        sigs: Vec<Sig>,
+    },
+    /// See [ExecuteMsg::ExtendDispute].
+    ExtendDispute {
+        params: Params,
+        extension: DisputeExtension,
+        sigs: Vec<Sig>,
+    },
+    /// See [ExecuteMsg::CancelDispute].
+    CancelDispute {
+        params: Params,
+        cancellation: DisputeCancellation,
+        sigs: Vec<Sig>,
+    },
+    /// See [ExecuteMsg::Withdraw].
     Withdraw { withdrawal: Withdrawal, sig: Sig },
+    /// See [ExecuteMsg::Novate].
+    Novate {
+        params: Params,
+        novation: Novation,
+        sigs: Vec<Sig>,
+    },
+    /// See [ExecuteMsg::Rollover].
+    Rollover {
+        old_channel: ChannelId,
+        new_params: Params,
+        sigs: Vec<Sig>,
+    },
+    /// See [ExecuteMsg::Splice].
+    ///
+    /// The deposits of the splice are part of the funds of the batch.
+    Splice {
+        params: Params,
+        splice: Splice,
+        sigs: Vec<Sig>,
+    },
+    /// See [ExecuteMsg::RevealPreimage].
+    RevealPreimage { preimage: Binary },
+    /// See [ExecuteMsg::Settle].
+    Settle {
+        params: Params,
+        state: State,
+        sigs: Vec<Sig>,
+        withdrawals: Vec<SignedWithdrawal>,
+    },
+}
+
+/// [WatcherAuth] together with the signature of its participant.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedWatcherAuth {
+    pub auth: WatcherAuth,
+    pub sig: Sig,
+}
+
+/// [Withdrawal] together with the signature of its participant.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedWithdrawal {
+    pub withdrawal: Withdrawal,
+    pub sig: Sig,
 }
 
 /// Message to query the state of the [crate::contract].
@@ -77,6 +445,15 @@ pub enum QueryMsg {
     Deposit(FundingId),
     // Returns the on-chain dispute for a channel.
     Dispute(ChannelId),
+    // Returns the on-chain deposits of all participants of a registered
+    // channel.
+    Holdings(ChannelId),
+    // Returns the phase of a channel and the status of its participants.
+    ChannelStatus { params: Params },
+    // Compares the liabilities of the contract with its bank balance.
+    Solvency {},
+    // Returns the configuration of the contract.
+    Config {},
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -84,3 +461,185 @@ pub struct DepositResponse(pub Vec<cosmwasm_std::Coin>);
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct DisputeResponse(pub Dispute);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Holding {
+    /// Index of the participant in [Params::participants].
+    pub part: PartIdx,
+    pub amount: Vec<cosmwasm_std::Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct HoldingsResponse(pub Vec<Holding>);
+
+/// Phase of a channel, see the state diagram in the README.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelPhase {
+    Open,
+    Disputed,
+    Concluded,
+    Withdrawn,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PartStatus {
+    /// Current on-chain holding of the participant.
+    pub holding: Vec<cosmwasm_std::Coin>,
+    /// Whether the participant withdrew the outcome.
+    pub withdrawn: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelStatusResponse {
+    pub phase: ChannelPhase,
+    /// Version of the registered state, if any.
+    pub version: Option<Version>,
+    /// Seconds until the dispute timeout ends, relative to the block time.
+    ///
+    /// Only set in [ChannelPhase::Disputed], zero once the timeout ran out.
+    pub timeout_in: Option<Seconds>,
+    /// Status of each participant in [Params::participants] order.
+    pub parts: Vec<PartStatus>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomSolvency {
+    pub denom: String,
+    /// Total amount that the contract owes to all funding ids.
+    pub liabilities: Uint128,
+    /// Bank balance of the contract.
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SolvencyResponse {
+    /// Whether the balance covers the liabilities for every denom.
+    pub solvent: bool,
+    /// All denoms that the contract owes.
+    pub denoms: Vec<DenomSolvency>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct ConfigResponse(pub Config);
+
+/// Data of the [ExecuteMsg::Deposit] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositResult {
+    pub funding_id: FundingId,
+    /// Holding of the funding id after the deposit.
+    pub holding: Vec<Coin>,
+}
+
+/// Data of the [ExecuteMsg::IbcDeposit] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcDepositResult {
+    /// The funds were credited to the funding id.
+    Deposited(DepositResult),
+    /// The deposit was rejected and the funds were sent back.
+    Refunded {
+        receiver: OnIdentity,
+        amount: Vec<Coin>,
+        /// Why the deposit was rejected.
+        reason: String,
+    },
+}
+
+/// Data of the [ExecuteMsg::Dispute] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeResult {
+    pub channel_id: ChannelId,
+    /// Version of the registered state.
+    pub version: Version,
+    /// End of the dispute period.
+    pub timeout: Timestamp,
+}
+
+/// Data of the [ExecuteMsg::Checkpoint] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CheckpointResult {
+    pub channel_id: ChannelId,
+    /// New checkpoint of the channel.
+    pub checkpoint: Version,
+}
+
+/// Data of the [ExecuteMsg::CancelDispute] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CancelDisputeResult {
+    pub channel_id: ChannelId,
+    /// New checkpoint of the channel.
+    pub checkpoint: Version,
+}
+
+/// Data of the [ExecuteMsg::Conclude] and [ExecuteMsg::ConcludeDispute]
+/// responses.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConcludeResult {
+    pub channel_id: ChannelId,
+    /// Outcome of each participant in [Params::participants] order.
+    pub outcome: Vec<Holding>,
+    /// Relayer fee that was paid to the sender, see [State::fee].
+    pub fee: Vec<Coin>,
+}
+
+/// Data of the [ExecuteMsg::Withdraw] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WithdrawResult {
+    pub funding_id: FundingId,
+    pub receiver: OnIdentity,
+    /// Amount that was sent to the receiver.
+    pub amount: Vec<Coin>,
+    /// Relayer fee that was paid to the sender, see [Withdrawal::fee].
+    pub fee: Vec<Coin>,
+}
+
+/// Data of the [ExecuteMsg::Settle] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SettleResult {
+    pub channel_id: ChannelId,
+    /// Payout of each participant in [Params::participants] order.
+    pub payouts: Vec<WithdrawResult>,
+}
+
+/// Data of the [ExecuteMsg::Novate] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NovationResult {
+    pub channel_id: ChannelId,
+    pub part: PartIdx,
+    /// Identity that signs for the participant from now on.
+    pub identity: OffIdentity,
+}
+
+/// Data of the [ExecuteMsg::Rollover] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RolloverResult {
+    /// Id of the new channel.
+    pub channel_id: ChannelId,
+    /// Deposits of the moved participants in the new channel.
+    pub deposits: Vec<DepositResult>,
+}
+
+/// Data of the [ExecuteMsg::Splice] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpliceResult {
+    pub channel_id: ChannelId,
+    /// New checkpoint of the channel.
+    pub version: Version,
+    /// Deposit of each participant after the splice.
+    pub holdings: Vec<Holding>,
+}
+
+/// Data of the [ExecuteMsg::RevealPreimage] response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RevealResult {
+    pub hash: Hash,
+    /// Time of the first reveal.
+    pub revealed_at: Timestamp,
+}
+
+/// Data of the [ExecuteMsg::Batch] response.
+///
+/// Contains the data of each operation in order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchResult(pub Vec<Option<Binary>>);
@@ -13,8 +13,358 @@
 //  limitations under the License.
 
 //! Definition of the on-chain storage containers.
-use crate::types::{ChannelId, Deposit, Dispute, FundingId};
-use cw_storage_plus::Map;
+use crate::{
+    crypto::{Hash, OffIdentity},
+    ensure,
+    error::ContractError,
+    types::{
+        calc_funding_id, Bond, ChannelId, Config, Deposit, Dispute, FundingId, Params, PartIdx,
+        RegisteredState, Reward, State, Version, WrappedBalance,
+    },
+};
+use cosmwasm_std::{Coin, Order, StdResult, Storage, Timestamp, Uint128, Uint64};
+use cw_storage_plus::{Item, Map, U16Key};
+use serde::{Deserialize, Serialize};
 
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Deposits of participants, grouped by channel.
+///
+/// Only contains channels whose [Params] are known, see [register_channel].
+pub const HOLDINGS: Map<(ChannelId, U16Key), Deposit> = Map::new("holdings");
+/// Resolves the funding ids of registered channels to [HOLDINGS] keys.
+pub const FUNDING_IDS: Map<FundingId, (ChannelId, PartIdx)> = Map::new("funding_ids");
+/// Deposits of funding ids whose channel is not registered yet.
+///
+/// All deposits were stored here before [HOLDINGS] was introduced.
 pub const DEPOSITS: Map<FundingId, Deposit> = Map::new("deposits");
 pub const DISPUTES: Map<ChannelId, Dispute> = Map::new("register");
+/// Params of every registered channel, see [register_channel].
+pub const PARAMS: Map<ChannelId, Params> = Map::new("params");
+/// Participants of registered channels that withdrew their outcome.
+pub const WITHDRAWN: Map<(ChannelId, U16Key), bool> = Map::new("withdrawn");
+/// Tombstones of fully withdrawn channels, holding their final version.
+///
+/// Such channels can not be used again, see [close_channel].
+pub const CLOSED: Map<ChannelId, Version> = Map::new("closed");
+/// Bonds of running disputes, see [crate::types::Config::dispute_bond].
+pub const BONDS: Map<ChannelId, Bond> = Map::new("bonds");
+/// Rewards of watchers that refuted a dispute on behalf of a participant.
+///
+/// Paid out of the outcome of the participant when the channel is
+/// concluded.
+pub const REWARDS: Map<(ChannelId, U16Key), Reward> = Map::new("rewards");
+/// Version floors of open channels.
+///
+/// Set by [crate::msg::ExecuteMsg::Checkpoint], a [crate::types::Splice] or
+/// a [crate::types::DisputeCancellation].
+/// States below the checkpoint can not be used on-chain, see
+/// [ensure_checkpoint].
+pub const CHECKPOINTS: Map<ChannelId, RegisteredState> = Map::new("checkpoints");
+/// Highest nonce of the [crate::types::DisputeExtension]s and
+/// [crate::types::DisputeCancellation]s that were applied to a channel.
+///
+/// Makes them single-use, since their signatures are public once applied.
+pub const DISPUTE_NONCES: Map<ChannelId, Uint64> = Map::new("dispute_nonces");
+/// Highest nonce of the [crate::types::Novation]s that were applied to a
+/// participant of a channel.
+///
+/// Keeps old novations from being replayed to hand a participant back to a
+/// previous identity.
+pub const NOVATION_NONCES: Map<(ChannelId, U16Key), Uint64> = Map::new("novation_nonces");
+/// Identities that replaced participants of a channel, see
+/// [crate::types::Novation].
+pub const AUTHORITIES: Map<(ChannelId, U16Key), OffIdentity> = Map::new("authorities");
+/// Revealed preimages of [crate::types::Lock] hashes and the time of their
+/// first reveal.
+pub const PREIMAGES: Map<Hash, Timestamp> = Map::new("preimages");
+/// Total amount per denom that the contract owes to all funding ids.
+///
+/// Covers [HOLDINGS], [DEPOSITS] and [BONDS], see
+/// [crate::msg::QueryMsg::Solvency].
+pub const LIABILITIES: Map<&str, Uint128> = Map::new("liabilities");
+
+/// Layout of [DISPUTES] before [crate::types::RegisteredState] was introduced.
+///
+/// Only used to migrate old entries, see [crate::contract::migrate].
+pub const LEGACY_DISPUTES: Map<ChannelId, LegacyDispute> = Map::new("register");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LegacyDispute {
+    pub state: State,
+    pub timeout: Timestamp,
+    pub concluded: bool,
+}
+
+/// Loads the [Config], which defaults for contracts instantiated without one.
+pub fn load_config(storage: &dyn Storage) -> StdResult<Config> {
+    Ok(CONFIG.may_load(storage)?.unwrap_or_default())
+}
+
+/// Registers the channel of `params` and returns its id.
+///
+/// Saves the params, indexes the funding ids of all participants and moves
+/// their deposits from [DEPOSITS] to [HOLDINGS].
+/// Does nothing for already registered channels.
+pub fn register_channel(
+    storage: &mut dyn Storage,
+    params: &Params,
+) -> Result<ChannelId, ContractError> {
+    let channel_id = params.channel_id()?;
+    ensure_not_closed(storage, &channel_id)?;
+    if PARAMS.has(storage, channel_id.clone()) {
+        return Ok(channel_id);
+    }
+    PARAMS.save(storage, channel_id.clone(), params)?;
+
+    for (i, part) in params.participants.iter().enumerate() {
+        let index = i as PartIdx;
+        let fid = calc_funding_id(&channel_id, part)?;
+        FUNDING_IDS.save(storage, fid.clone(), &(channel_id.clone(), index))?;
+        if let Some(deposit) = DEPOSITS.may_load(storage, fid.clone())? {
+            DEPOSITS.remove(storage, fid);
+            HOLDINGS.update(storage, (channel_id.clone(), index.into()), |holding| {
+                holding.unwrap_or_default().checked_add(&deposit)
+            })?;
+        }
+    }
+    Ok(channel_id)
+}
+
+/// Loads the deposit of a funding id.
+///
+/// Uses [HOLDINGS] once the channel is registered and [DEPOSITS] before.
+pub fn load_deposit(storage: &dyn Storage, fid: &FundingId) -> StdResult<Option<Deposit>> {
+    match FUNDING_IDS.may_load(storage, fid.clone())? {
+        Some((channel_id, index)) => HOLDINGS.may_load(storage, (channel_id, index.into())),
+        None => DEPOSITS.may_load(storage, fid.clone()),
+    }
+}
+
+/// Saves the deposit of a funding id, see [load_deposit].
+pub fn save_deposit(
+    storage: &mut dyn Storage,
+    fid: &FundingId,
+    deposit: &Deposit,
+) -> StdResult<()> {
+    match FUNDING_IDS.may_load(storage, fid.clone())? {
+        Some((channel_id, index)) => HOLDINGS.save(storage, (channel_id, index.into()), deposit),
+        None => DEPOSITS.save(storage, fid.clone(), deposit),
+    }
+}
+
+/// Removes the deposit of a funding id, see [load_deposit].
+pub fn remove_deposit(storage: &mut dyn Storage, fid: &FundingId) -> StdResult<()> {
+    match FUNDING_IDS.may_load(storage, fid.clone())? {
+        Some((channel_id, index)) => HOLDINGS.remove(storage, (channel_id, index.into())),
+        None => DEPOSITS.remove(storage, fid.clone()),
+    }
+    Ok(())
+}
+
+/// Fails for channels that were closed by [close_channel].
+pub fn ensure_not_closed(
+    storage: &dyn Storage,
+    channel_id: &ChannelId,
+) -> Result<(), ContractError> {
+    ensure!(
+        !CLOSED.has(storage, channel_id.clone()),
+        ContractError::ChannelClosed {}
+    );
+    Ok(())
+}
+
+/// Fails for states with a version below the [CHECKPOINTS] entry of their
+/// channel.
+pub fn ensure_checkpoint(
+    storage: &dyn Storage,
+    channel_id: &ChannelId,
+    version: Version,
+) -> Result<(), ContractError> {
+    if let Some(checkpoint) = CHECKPOINTS.may_load(storage, channel_id.clone())? {
+        ensure!(
+            version >= checkpoint.version,
+            ContractError::VersionBelowCheckpoint {}
+        );
+    }
+    Ok(())
+}
+
+/// Consumes `nonce` of a [crate::types::DisputeExtension] or
+/// [crate::types::DisputeCancellation], see [DISPUTE_NONCES].
+pub fn use_dispute_nonce(
+    storage: &mut dyn Storage,
+    channel_id: &ChannelId,
+    nonce: Uint64,
+) -> Result<(), ContractError> {
+    if let Some(last) = DISPUTE_NONCES.may_load(storage, channel_id.clone())? {
+        ensure!(nonce > last, ContractError::NonceUsed {});
+    }
+    DISPUTE_NONCES.save(storage, channel_id.clone(), &nonce)?;
+    Ok(())
+}
+
+/// Consumes `nonce` of a [crate::types::Novation] of participant `part`,
+/// see [NOVATION_NONCES].
+pub fn use_novation_nonce(
+    storage: &mut dyn Storage,
+    channel_id: &ChannelId,
+    part: PartIdx,
+    nonce: Uint64,
+) -> Result<(), ContractError> {
+    let key = (channel_id.clone(), part.into());
+    if let Some(last) = NOVATION_NONCES.may_load(storage, key.clone())? {
+        ensure!(nonce > last, ContractError::NonceUsed {});
+    }
+    NOVATION_NONCES.save(storage, key, &nonce)?;
+    Ok(())
+}
+
+/// Returns the identities that sign for the participants of `params`,
+/// honoring the [AUTHORITIES] that replaced them.
+pub fn load_signers(
+    storage: &dyn Storage,
+    params: &Params,
+) -> Result<Vec<OffIdentity>, ContractError> {
+    let channel_id = params.channel_id()?;
+    let mut signers = Vec::with_capacity(params.participants.len());
+    for (i, part) in params.participants.iter().enumerate() {
+        let key = (channel_id.clone(), (i as PartIdx).into());
+        signers.push(
+            AUTHORITIES
+                .may_load(storage, key)?
+                .unwrap_or_else(|| part.clone()),
+        );
+    }
+    Ok(signers)
+}
+
+/// Returns the identity that signs for the participant `part` of a channel,
+/// see [load_signers].
+pub fn load_authority(
+    storage: &dyn Storage,
+    channel_id: &ChannelId,
+    part: &OffIdentity,
+) -> Result<OffIdentity, ContractError> {
+    let fid = calc_funding_id(channel_id, part)?;
+    let authority = match FUNDING_IDS.may_load(storage, fid)? {
+        Some((channel_id, index)) => AUTHORITIES.may_load(storage, (channel_id, index.into()))?,
+        None => None,
+    };
+    Ok(authority.unwrap_or_else(|| part.clone()))
+}
+
+/// Marks the participant of a funding id as withdrawn.
+///
+/// Closes the channel once all participants withdrew.
+/// Does nothing for funding ids of unregistered channels.
+pub fn mark_withdrawn(storage: &mut dyn Storage, fid: &FundingId) -> Result<(), ContractError> {
+    let (channel_id, index) = match FUNDING_IDS.may_load(storage, fid.clone())? {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+    WITHDRAWN.save(storage, (channel_id.clone(), index.into()), &true)?;
+
+    let num_parts = PARAMS.load(storage, channel_id.clone())?.participants.len();
+    let withdrawn = WITHDRAWN
+        .prefix(channel_id.clone())
+        .keys(storage, None, None, Order::Ascending)
+        .count();
+    if withdrawn == num_parts {
+        let version = DISPUTES.load(storage, channel_id.clone())?.state.version;
+        close_channel(storage, &channel_id, version, num_parts)?;
+    }
+    Ok(())
+}
+
+/// Removes all entries of a fully withdrawn channel and leaves a tombstone
+/// with the final `version` in [CLOSED].
+///
+/// The [FUNDING_IDS] are kept to reject further deposits into the channel.
+pub fn close_channel(
+    storage: &mut dyn Storage,
+    channel_id: &ChannelId,
+    version: Version,
+    num_parts: usize,
+) -> Result<(), ContractError> {
+    CLOSED.save(storage, channel_id.clone(), &version)?;
+    DISPUTES.remove(storage, channel_id.clone());
+    PARAMS.remove(storage, channel_id.clone());
+    CHECKPOINTS.remove(storage, channel_id.clone());
+    DISPUTE_NONCES.remove(storage, channel_id.clone());
+    for i in 0..num_parts {
+        let index = i as PartIdx;
+        HOLDINGS.remove(storage, (channel_id.clone(), index.into()));
+        WITHDRAWN.remove(storage, (channel_id.clone(), index.into()));
+        AUTHORITIES.remove(storage, (channel_id.clone(), index.into()));
+        NOVATION_NONCES.remove(storage, (channel_id.clone(), index.into()));
+    }
+    Ok(())
+}
+
+/// Adds `amount` to the [LIABILITIES].
+pub fn add_liabilities(storage: &mut dyn Storage, amount: &WrappedBalance) -> StdResult<()> {
+    for coin in amount.0 .0.iter() {
+        LIABILITIES.update(storage, &coin.denom, |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default().checked_add(coin.amount)?)
+        })?;
+    }
+    Ok(())
+}
+
+/// Loads the [LIABILITIES] of all denoms in `amount`.
+pub fn load_liabilities(
+    storage: &dyn Storage,
+    amount: &WrappedBalance,
+) -> StdResult<WrappedBalance> {
+    let mut total = vec![];
+    for coin in amount.0 .0.iter() {
+        let liability = LIABILITIES.may_load(storage, &coin.denom)?;
+        total.push(Coin::new(liability.unwrap_or_default().u128(), &coin.denom));
+    }
+    Ok(total.into())
+}
+
+/// Subtracts `amount` from the [LIABILITIES].
+///
+/// Removes denoms that are no longer owed.
+pub fn sub_liabilities(storage: &mut dyn Storage, amount: &WrappedBalance) -> StdResult<()> {
+    for coin in amount.0 .0.iter() {
+        let total = LIABILITIES
+            .may_load(storage, &coin.denom)?
+            .unwrap_or_default()
+            .checked_sub(coin.amount)?;
+        if total.is_zero() {
+            LIABILITIES.remove(storage, &coin.denom);
+        } else {
+            LIABILITIES.save(storage, &coin.denom, &total)?;
+        }
+    }
+    Ok(())
+}
+
+/// Sums up all [HOLDINGS], [DEPOSITS] and [BONDS].
+pub fn sum_deposits(storage: &dyn Storage) -> Result<WrappedBalance, ContractError> {
+    let mut sum = WrappedBalance::default();
+    for item in HOLDINGS.range(storage, None, None, Order::Ascending) {
+        sum = sum.checked_add(&item?.1)?;
+    }
+    for item in DEPOSITS.range(storage, None, None, Order::Ascending) {
+        sum = sum.checked_add(&item?.1)?;
+    }
+    for item in BONDS.range(storage, None, None, Order::Ascending) {
+        sum = sum.checked_add(&item?.1.amount.into())?;
+    }
+    Ok(sum)
+}
+
+/// Recomputes the [LIABILITIES] from all deposits.
+pub fn reset_liabilities(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let denoms: Vec<Vec<u8>> = LIABILITIES
+        .keys(storage, None, None, Order::Ascending)
+        .collect();
+    for denom in denoms {
+        LIABILITIES.remove(storage, &String::from_utf8_lossy(&denom));
+    }
+    let total = sum_deposits(storage)?;
+    Ok(add_liabilities(storage, &total)?)
+}
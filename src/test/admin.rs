@@ -0,0 +1,186 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    storage::CONFIG,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    testing::{mock_env, mock_info},
+    Addr, Response,
+};
+
+pub const ADMIN: &str = "admin";
+
+fn admin_init() -> (Setup, Deps) {
+    do_init_with(InitMsg {
+        admin: Some(ADMIN.into()),
+        ..Default::default()
+    })
+}
+
+fn do_admin(deps: &mut Deps, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    execute_checked(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+}
+
+fn do_pause(deps: &mut Deps, deposits: bool, disputes: bool) {
+    let msg = ExecuteMsg::SetPaused { deposits, disputes };
+    do_admin(deps, ADMIN, msg).unwrap();
+}
+
+#[test]
+fn pause_deposits() {
+    let (s, mut deps) = admin_init();
+    do_pause(&mut deps, true, false);
+
+    assert_eq!(
+        do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap_err(),
+        ContractError::Paused {}
+    );
+    // Disputes are still possible.
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    // Resume.
+    do_pause(&mut deps, false, false);
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+}
+
+#[test]
+fn pause_disputes() {
+    let (s, mut deps) = admin_init();
+    do_pause(&mut deps, true, true);
+
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap_err(),
+        ContractError::Paused {}
+    );
+}
+
+/// Pausing disputes does not keep running disputes from being refuted.
+#[test]
+fn pause_allows_refutation() {
+    let (s, mut deps) = admin_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+    do_pause(&mut deps, true, true);
+
+    let mut state = s.nfinal_state.clone();
+    state.version = (state.version.u64() + 1).into();
+    let sigs = fully_sign(&state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &state, &sigs).unwrap();
+    let status = query_channel_status(deps.as_mut(), mock_env(), &s.params);
+    assert_eq!(status.version, Some(state.version));
+}
+
+/// Users can always exit their channels.
+#[test]
+fn pause_allows_exit() {
+    let (s, mut deps) = admin_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    do_pause(&mut deps, true, true);
+
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+}
+
+#[test]
+fn admin_unauthorized() {
+    let (_, mut deps) = admin_init();
+    let msgs = [
+        ExecuteMsg::SetPaused {
+            deposits: true,
+            disputes: true,
+        },
+        ExecuteMsg::TransferAdmin {
+            admin: ALICE.into(),
+        },
+        ExecuteMsg::RenounceAdmin {},
+    ];
+    for msg in msgs {
+        assert_eq!(
+            do_admin(&mut deps, ALICE, msg).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+    }
+}
+
+#[test]
+fn admin_transfer() {
+    let (_, mut deps) = admin_init();
+    let msg = ExecuteMsg::TransferAdmin {
+        admin: ALICE.into(),
+    };
+    do_admin(&mut deps, ADMIN, msg).unwrap();
+    let config = CONFIG.load(deps.as_ref().storage).unwrap();
+    assert_eq!(config.admin, Some(Addr::unchecked(ALICE)));
+
+    // The old admin lost its role.
+    let msg = ExecuteMsg::SetPaused {
+        deposits: true,
+        disputes: false,
+    };
+    assert_eq!(
+        do_admin(&mut deps, ADMIN, msg.clone()).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+    do_admin(&mut deps, ALICE, msg).unwrap();
+}
+
+#[test]
+fn admin_renounce() {
+    let (_, mut deps) = admin_init();
+    do_admin(&mut deps, ADMIN, ExecuteMsg::RenounceAdmin {}).unwrap();
+    let config = CONFIG.load(deps.as_ref().storage).unwrap();
+    assert_eq!(config.admin, None);
+
+    let msg = ExecuteMsg::SetPaused {
+        deposits: true,
+        disputes: false,
+    };
+    assert_eq!(
+        do_admin(&mut deps, ADMIN, msg).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+}
+
+/// Without an admin, the contract can not be paused.
+#[test]
+fn admin_none() {
+    let (_, mut deps) = do_init();
+    let msg = ExecuteMsg::SetPaused {
+        deposits: true,
+        disputes: false,
+    };
+    assert_eq!(
+        do_admin(&mut deps, ADMIN, msg).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+}
@@ -0,0 +1,277 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    contract::migrate,
+    crypto::Sig,
+    error::ContractError,
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        random::random_account,
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, mock_info},
+    Env, Response,
+};
+use k256::ecdsa::SigningKey;
+
+const DELAY: u64 = 30;
+
+/// Returns a funded channel whose [Params] name an arbiter with [DELAY].
+fn arbiter_init() -> (Setup, SigningKey, Deps) {
+    arbiter_init_with(DELAY)
+}
+
+fn arbiter_init_with(delay: u64) -> (Setup, SigningKey, Deps) {
+    let (mut s, mut deps) = do_init();
+    let (key, identity) = random_account(&mut rand::thread_rng());
+    s.params.arbiter = Some(Arbiter {
+        identity,
+        delay: delay.into(),
+    });
+    s.cid = s.params.channel_id().unwrap();
+    s.fids = s
+        .params
+        .participants
+        .iter()
+        .map(|part| calc_funding_id(&s.cid, part).unwrap())
+        .collect();
+    s.final_state.channel_id = s.cid.clone();
+    s.nfinal_state.channel_id = s.cid.clone();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    (s, key, deps)
+}
+
+/// Returns a newer state of `s` in which Alice pays 5 ATOM to Bob.
+fn resolution(s: &Setup) -> State {
+    let mut state = s.nfinal_state.clone();
+    state.version = 124u64.into();
+    state.balances[0] = cw0::NativeBalance(vec![coin(15, DENOMS[1]), coin(2, DENOMS[0])]);
+    state.balances[1] = cw0::NativeBalance(vec![coin(15, DENOMS[1])]);
+    state
+}
+
+fn arbitrate(
+    deps: &mut Deps,
+    env: Env,
+    s: &Setup,
+    state: &State,
+    sig: Sig,
+    conclude: bool,
+) -> Result<Response, ContractError> {
+    let msg = if conclude {
+        ExecuteMsg::Conclude {
+            params: s.params.clone(),
+            state: state.clone(),
+            sigs: vec![],
+            arbiter_sig: Some(sig),
+        }
+    } else {
+        ExecuteMsg::Dispute {
+            params: s.params.clone(),
+            state: state.clone(),
+            sigs: vec![],
+            arbiter_sig: Some(sig),
+            watcher: None,
+        }
+    };
+    execute_checked(deps.as_mut(), env, mock_info(ALICE, &[]), msg)
+}
+
+fn arbitrated(res: &Response) -> Vec<(String, String)> {
+    let event = res.events.iter().find(|e| e.ty == "arbitrate").unwrap();
+    event
+        .attributes
+        .iter()
+        .map(|a| (a.key.clone(), a.value.clone()))
+        .collect()
+}
+
+fn after_delay() -> Env {
+    advance_time(mock_env(), DELAY.into())
+}
+
+/// Channels without arbiter keep their encoding and channel id.
+#[test]
+fn arbiter_not_encoded() {
+    let s = new_setup();
+    let encoded = encode_obj(&s.params).unwrap();
+    assert!(!String::from_utf8(encoded).unwrap().contains("arbiter"));
+}
+
+#[test]
+fn arbiter_conclude() {
+    let (s, key, mut deps) = arbiter_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let state = resolution(&s);
+    let res = arbitrate(
+        &mut deps,
+        after_delay(),
+        &s,
+        &state,
+        sign(&state, &key),
+        true,
+    )
+    .unwrap();
+    assert_eq!(arbitrated(&res)[0], ("action".into(), "conclude".into()));
+    assert_eq!(
+        outcome(&res),
+        vec![state.balances[0].0.clone(), state.balances[1].0.clone()]
+    );
+    assert_eq!(
+        query_deposit(deps.as_mut(), s.fids[1].clone()),
+        state.balances[1].0.clone().into()
+    );
+}
+
+#[test]
+fn arbiter_dispute() {
+    let (s, key, mut deps) = arbiter_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let state = resolution(&s);
+    let res = arbitrate(
+        &mut deps,
+        after_delay(),
+        &s,
+        &state,
+        sign(&state, &key),
+        false,
+    )
+    .unwrap();
+    assert_eq!(arbitrated(&res)[0], ("action".into(), "dispute".into()));
+    let result: DisputeResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.version, state.version);
+}
+
+/// Disputes must be open for the arbiter delay before they can be arbitrated.
+#[test]
+fn arbiter_too_early() {
+    let (s, key, mut deps) = arbiter_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let state = resolution(&s);
+    let early = advance_time(mock_env(), (DELAY - 1).into());
+    for conclude in [true, false] {
+        assert_eq!(
+            arbitrate(
+                &mut deps,
+                early.clone(),
+                &s,
+                &state,
+                sign(&state, &key),
+                conclude
+            )
+            .unwrap_err(),
+            ContractError::ArbitrationTooEarly {}
+        );
+    }
+}
+
+/// The arbiter delay can not exceed the maximal dispute duration.
+#[test]
+fn arbiter_delay_limit() {
+    let (s, _, mut deps) = arbiter_init_with(u64::MAX);
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap_err(),
+        ContractError::InvalidArbiterDelay {}
+    );
+}
+
+/// Channels that were registered without limits can not overflow the
+/// arbitration time.
+#[test]
+fn arbiter_delay_overflow() {
+    let (s, key, mut deps) = arbiter_init_with(u64::MAX);
+    let msg = MigrateMsg {
+        compact_disputes: false,
+        channels: vec![s.params.clone()],
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let state = resolution(&s);
+    assert_eq!(
+        arbitrate(
+            &mut deps,
+            after_delay(),
+            &s,
+            &state,
+            sign(&state, &key),
+            false
+        )
+        .unwrap_err(),
+        ContractError::ArbitrationTooEarly {}
+    );
+}
+
+#[test]
+fn arbiter_without_dispute() {
+    let (s, key, mut deps) = arbiter_init();
+    let state = resolution(&s);
+    for conclude in [true, false] {
+        assert_eq!(
+            arbitrate(
+                &mut deps,
+                after_delay(),
+                &s,
+                &state,
+                sign(&state, &key),
+                conclude
+            )
+            .unwrap_err(),
+            ContractError::ArbitrationWithoutDispute {}
+        );
+    }
+}
+
+#[test]
+fn arbiter_wrong_sig() {
+    let (s, _, mut deps) = arbiter_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let state = resolution(&s);
+    let sig = sign(&state, &s.keys[0]);
+    assert_eq!(
+        arbitrate(&mut deps, after_delay(), &s, &state, sig, true).unwrap_err(),
+        ContractError::WrongArbiterSignature {}
+    );
+}
+
+#[test]
+fn arbiter_not_set() {
+    let (s, mut deps) = do_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let state = resolution(&s);
+    let sig = sign(&state, &s.keys[0]);
+    assert_eq!(
+        arbitrate(&mut deps, after_delay(), &s, &state, sig, true).unwrap_err(),
+        ContractError::ArbiterNotSet {}
+    );
+}
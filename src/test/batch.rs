@@ -0,0 +1,148 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, mock_info},
+    Addr, BankMsg, Coin, CosmosMsg, Response,
+};
+
+fn do_batch(
+    deps: &mut Deps,
+    funds: &[Coin],
+    ops: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    let info = mock_info(ALICE, funds);
+    execute_checked(deps.as_mut(), mock_env(), info, ExecuteMsg::Batch(ops))
+}
+
+fn deposit_ops(s: &Setup) -> Vec<Operation> {
+    s.fids
+        .iter()
+        .zip(s.alloc.iter())
+        .map(|(fid, bals)| Operation::Deposit {
+            funding_id: fid.clone(),
+            amount: bals.clone().into(),
+        })
+        .collect()
+}
+
+/// Funds multiple participants at once.
+#[test]
+fn batch_deposit() {
+    let (s, mut deps) = do_init();
+    let funds: Vec<Coin> = s.outcome.clone().into();
+
+    let res = do_batch(&mut deps, &funds, deposit_ops(&s)).unwrap();
+    let BatchResult(data) = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(data.len(), 2);
+    for (i, fid) in s.fids.iter().enumerate() {
+        assert_eq!(query_deposit(deps.as_mut(), fid.clone()), s.alloc[i]);
+        let result: DepositResult = from_binary(data[i].as_ref().unwrap()).unwrap();
+        assert_eq!(result.holding, Vec::<Coin>::from(s.alloc[i].clone()));
+    }
+}
+
+/// The funds must match the allocation exactly.
+#[test]
+fn batch_funds_mismatch() {
+    let (s, mut deps) = do_init();
+    let outcome: Vec<Coin> = s.outcome.clone().into();
+    let leftover: Vec<Coin> = s
+        .outcome
+        .checked_add(&vec![coin(1, DENOMS[0])].into())
+        .unwrap()
+        .into();
+
+    for funds in [vec![], s.alloc[0].clone().into(), leftover] {
+        assert_eq!(
+            do_batch(&mut deps, &funds, deposit_ops(&s)).unwrap_err(),
+            ContractError::BatchFundsMismatch {}
+        );
+    }
+    assert_eq!(
+        do_batch(&mut deps, &outcome, vec![]).unwrap_err(),
+        ContractError::BatchFundsMismatch {}
+    );
+}
+
+/// Concludes a channel and withdraws all outcomes in one transaction.
+#[test]
+fn batch_conclude_withdraw() {
+    let (s, mut deps) = funded_init();
+
+    let mut ops = vec![Operation::Conclude {
+        params: s.params.clone(),
+        state: s.final_state.clone(),
+        sigs: fully_sign(&s.final_state, &s.keys),
+        arbiter_sig: None,
+    }];
+    for (i, receiver) in [ALICE, BOB].iter().enumerate() {
+        let withdrawal = Withdrawal {
+            channel_id: s.cid.clone(),
+            part: s.params.participants[i].clone(),
+            receiver: Addr::unchecked(*receiver),
+            fee: vec![],
+        };
+        let sig = sign(&withdrawal, &s.keys[i]);
+        ops.push(Operation::Withdraw { withdrawal, sig });
+    }
+
+    let res = do_batch(&mut deps, &[], ops).unwrap();
+    let sends: Vec<_> = res.messages.iter().map(|m| m.msg.clone()).collect();
+    assert_eq!(
+        sends,
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ALICE.into(),
+                amount: s.alloc[0].clone().into(),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BOB.into(),
+                amount: s.alloc[1].clone().into(),
+            }),
+        ]
+    );
+}
+
+/// A failing operation fails the whole batch.
+#[test]
+fn batch_fails_atomically() {
+    let (s, mut deps) = do_init();
+    let funds: Vec<Coin> = s.outcome.clone().into();
+    let mut ops = deposit_ops(&s);
+    // Withdrawing from an open channel fails.
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    ops.push(Operation::Withdraw { withdrawal, sig });
+
+    assert_eq!(
+        do_batch(&mut deps, &funds, ops).unwrap_err(),
+        ContractError::UnknownChannel {}
+    );
+}
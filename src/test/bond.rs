@@ -0,0 +1,231 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    storage::BONDS,
+    test::common::{crypto::fully_sign, setup::*},
+    types::*,
+};
+use cosmwasm_std::{
+    coin, coins,
+    testing::{mock_env, mock_info},
+    Addr, BankMsg, Coin, CosmosMsg, Env, Response, Uint64,
+};
+
+const BOND: &str = "bond";
+
+fn bond_init() -> (Setup, Deps) {
+    let (s, mut deps) = do_init_with(InitMsg {
+        dispute_bond: coins(5, BOND),
+        ..Default::default()
+    });
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    (s, deps)
+}
+
+fn dispute_with(
+    deps: &mut Deps,
+    params: &Params,
+    state: &State,
+    keys: &[k256::ecdsa::SigningKey],
+    sender: &str,
+    funds: &[Coin],
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Dispute {
+        params: params.clone(),
+        state: state.clone(),
+        sigs: fully_sign(state, keys),
+        arbiter_sig: None,
+        watcher: None,
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(sender, funds), msg)
+}
+
+fn bond_send(to: &str) -> CosmosMsg {
+    CosmosMsg::Bank(BankMsg::Send {
+        to_address: to.into(),
+        amount: coins(5, BOND),
+    })
+}
+
+fn newer(state: &State) -> State {
+    let mut state = state.clone();
+    state.version = (state.version.u64() + 1).into();
+    state
+}
+
+fn timed_out(s: &Setup) -> Env {
+    advance_time(mock_env(), s.params.dispute_duration + Uint64::from(1u64))
+}
+
+/// Opening a dispute requires exactly the bond.
+#[test]
+fn bond_required() {
+    let (s, mut deps) = bond_init();
+    for funds in [
+        vec![],
+        coins(4, BOND),
+        vec![coin(5, BOND), coin(1, DENOMS[0])],
+    ] {
+        assert_eq!(
+            dispute_with(
+                &mut deps,
+                &s.params,
+                &s.nfinal_state,
+                &s.keys,
+                ALICE,
+                &funds
+            )
+            .unwrap_err(),
+            ContractError::WrongBond {}
+        );
+    }
+    dispute_with(
+        &mut deps,
+        &s.params,
+        &s.nfinal_state,
+        &s.keys,
+        ALICE,
+        &coins(5, BOND),
+    )
+    .unwrap();
+    assert_eq!(
+        BONDS.load(deps.as_ref().storage, s.cid.clone()).unwrap(),
+        Bond {
+            owner: Addr::unchecked(ALICE),
+            amount: coins(5, BOND),
+        }
+    );
+}
+
+/// The bond goes to the first refuting party.
+#[test]
+fn bond_refuted() {
+    let (s, mut deps) = bond_init();
+    let state = s.nfinal_state.clone();
+    dispute_with(
+        &mut deps,
+        &s.params,
+        &state,
+        &s.keys,
+        ALICE,
+        &coins(5, BOND),
+    )
+    .unwrap();
+
+    let state = newer(&state);
+    assert_eq!(
+        dispute_with(&mut deps, &s.params, &state, &s.keys, BOB, &coins(5, BOND)).unwrap_err(),
+        ContractError::WrongBond {}
+    );
+    let res = dispute_with(&mut deps, &s.params, &state, &s.keys, BOB, &[]).unwrap();
+    assert_eq!(res.messages[0].msg, bond_send(BOB));
+
+    // Nothing left for later refutations or the conclusion.
+    let res = dispute_with(&mut deps, &s.params, &newer(&state), &s.keys, ALICE, &[]).unwrap();
+    assert!(res.messages.is_empty());
+    let res = do_conclude_dispute(deps.as_mut(), timed_out(&s), &s.cid, None).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+/// An unrefuted dispute returns the bond at conclusion.
+#[test]
+fn bond_returned() {
+    let (s, mut deps) = bond_init();
+    dispute_with(
+        &mut deps,
+        &s.params,
+        &s.nfinal_state,
+        &s.keys,
+        BOB,
+        &coins(5, BOND),
+    )
+    .unwrap();
+
+    let res = do_conclude_dispute(deps.as_mut(), timed_out(&s), &s.cid, None).unwrap();
+    assert_eq!(res.messages[0].msg, bond_send(BOB));
+    assert!(!BONDS.has(deps.as_ref().storage, s.cid.clone()));
+}
+
+/// Concluding with a higher final state counts as refutation.
+#[test]
+fn bond_refuted_by_conclude() {
+    let (s, mut deps) = bond_init();
+    let mut state = s.final_state.clone();
+    state.version = (state.version.u64() - 1).into();
+    state.finalized = false;
+    dispute_with(&mut deps, &s.params, &state, &s.keys, BOB, &coins(5, BOND)).unwrap();
+
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    let res = do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+    assert_eq!(res.messages[0].msg, bond_send(ALICE));
+}
+
+/// The bond of the params overrides the one of the config.
+#[test]
+fn bond_params() {
+    let (mut s, mut deps) = do_init_with(InitMsg {
+        dispute_bond: coins(5, BOND),
+        ..Default::default()
+    });
+    s.params.bond = coins(1, DENOMS[0]);
+    let mut state = s.nfinal_state.clone();
+    state.channel_id = s.params.channel_id().unwrap();
+    assert_ne!(state.channel_id, s.cid);
+
+    assert_eq!(
+        dispute_with(
+            &mut deps,
+            &s.params,
+            &state,
+            &s.keys,
+            ALICE,
+            &coins(5, BOND)
+        )
+        .unwrap_err(),
+        ContractError::WrongBond {}
+    );
+    dispute_with(
+        &mut deps,
+        &s.params,
+        &state,
+        &s.keys,
+        ALICE,
+        &coins(1, DENOMS[0]),
+    )
+    .unwrap();
+}
+
+/// Bonds are part of the batch allocation.
+#[test]
+fn bond_batch() {
+    let (s, mut deps) = do_init_with(InitMsg {
+        dispute_bond: coins(5, BOND),
+        ..Default::default()
+    });
+    let ops = vec![Operation::Dispute {
+        params: s.params.clone(),
+        state: s.nfinal_state.clone(),
+        sigs: fully_sign(&s.nfinal_state, &s.keys),
+        arbiter_sig: None,
+        watcher: None,
+        bond: coins(5, BOND),
+    }];
+    let info = mock_info(ALICE, &coins(5, BOND));
+    execute_checked(deps.as_mut(), mock_env(), info, ExecuteMsg::Batch(ops)).unwrap();
+    assert!(BONDS.has(deps.as_ref().storage, s.cid.clone()));
+}
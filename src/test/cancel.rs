@@ -0,0 +1,133 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    coins, from_binary,
+    testing::{mock_env, mock_info},
+    BankMsg, CosmosMsg, Response,
+};
+
+fn do_cancel(
+    deps: &mut Deps,
+    s: &Setup,
+    version: Version,
+    nonce: u64,
+) -> Result<Response, ContractError> {
+    let cancellation = DisputeCancellation {
+        channel_id: s.cid.clone(),
+        version,
+        nonce: nonce.into(),
+    };
+    let msg = ExecuteMsg::CancelDispute {
+        params: s.params.clone(),
+        cancellation: cancellation.clone(),
+        sigs: s.keys.iter().map(|key| sign(&cancellation, key)).collect(),
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(BOB, &[]), msg)
+}
+
+#[test]
+fn cancel_ok() {
+    let (s, mut deps) = disputed_init();
+    let res = do_cancel(&mut deps, &s, s.nfinal_state.version, 1).unwrap();
+    let result: CancelDisputeResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.checkpoint, s.nfinal_state.version);
+
+    let status = query_channel_status(deps.as_mut(), mock_env(), &s.params);
+    assert_eq!(status.phase, ChannelPhase::Open);
+
+    // The channel can be disputed again, but not with an older state.
+    let mut old = s.nfinal_state.clone();
+    old.version = 122u64.into();
+    let sigs = fully_sign(&old, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &old, &sigs).unwrap_err(),
+        ContractError::VersionBelowCheckpoint {}
+    );
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+}
+
+/// The dispute bond goes back to the account that opened the dispute.
+#[test]
+fn cancel_returns_bond() {
+    let msg = InitMsg {
+        dispute_bond: coins(5, DENOMS[1]),
+        ..Default::default()
+    };
+    let (s, mut deps) = disputed_init_with(msg);
+    let res = do_cancel(&mut deps, &s, s.nfinal_state.version, 1).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: ALICE.into(),
+            amount: coins(5, DENOMS[1]),
+        })
+    );
+}
+
+#[test]
+fn cancel_wrong_version() {
+    let (s, mut deps) = disputed_init();
+    assert_eq!(
+        do_cancel(&mut deps, &s, 122u64.into(), 1).unwrap_err(),
+        ContractError::WrongDisputeVersion {}
+    );
+}
+
+#[test]
+fn cancel_concluded() {
+    let (s, mut deps) = disputed_init();
+    let env = advance_time(mock_env(), 60u64.into());
+    do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap();
+    assert_eq!(
+        do_cancel(&mut deps, &s, s.nfinal_state.version, 1).unwrap_err(),
+        ContractError::AlreadyConcluded {}
+    );
+}
+
+/// Applied cancellations can not be replayed on a later dispute of the same
+/// version.
+#[test]
+fn cancel_replay() {
+    let (s, mut deps) = disputed_init();
+    do_cancel(&mut deps, &s, s.nfinal_state.version, 1).unwrap();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    assert_eq!(
+        do_cancel(&mut deps, &s, s.nfinal_state.version, 1).unwrap_err(),
+        ContractError::NonceUsed {}
+    );
+    let env = advance_time(mock_env(), s.params.dispute_duration);
+    do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap();
+}
+
+#[test]
+fn cancel_new_nonce() {
+    let (s, mut deps) = disputed_init();
+    do_cancel(&mut deps, &s, s.nfinal_state.version, 1).unwrap();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+    do_cancel(&mut deps, &s, s.nfinal_state.version, 2).unwrap();
+}
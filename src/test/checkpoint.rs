@@ -0,0 +1,119 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    crypto::Sig,
+    error::ContractError,
+    msg::*,
+    test::common::{crypto::fully_sign, setup::*},
+    types::*,
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_env, mock_info},
+    Response,
+};
+
+fn do_checkpoint(
+    deps: &mut Deps,
+    s: &Setup,
+    state: &State,
+    sigs: Vec<Sig>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Checkpoint {
+        params: s.params.clone(),
+        state: state.clone(),
+        sigs,
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg)
+}
+
+fn with_version(state: &State, version: u64) -> State {
+    let mut state = state.clone();
+    state.version = version.into();
+    state
+}
+
+#[test]
+fn checkpoint_ok() {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    let res = do_checkpoint(&mut deps, &s, &s.nfinal_state, sigs).unwrap();
+    let result: CheckpointResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.checkpoint, s.nfinal_state.version);
+
+    // No dispute was started.
+    let status = query_channel_status(deps.as_mut(), mock_env(), &s.params);
+    assert_eq!(status.phase, ChannelPhase::Open);
+
+    // Older states can neither be disputed nor concluded.
+    let old = with_version(&s.nfinal_state, 122);
+    let sigs = fully_sign(&old, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &old, &sigs).unwrap_err(),
+        ContractError::VersionBelowCheckpoint {}
+    );
+    let old = with_version(&s.final_state, 122);
+    let sigs = fully_sign(&old, &s.keys);
+    assert_eq!(
+        do_conclude(deps.as_mut(), &s.params, &old, &sigs).unwrap_err(),
+        ContractError::VersionBelowCheckpoint {}
+    );
+    // The checkpointed version itself meets the floor.
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+}
+
+/// Checkpoints only move forward.
+#[test]
+fn checkpoint_lower_version() {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_checkpoint(&mut deps, &s, &s.nfinal_state, sigs).unwrap();
+
+    let old = with_version(&s.nfinal_state, 122);
+    let sigs = fully_sign(&old, &s.keys);
+    assert_eq!(
+        do_checkpoint(&mut deps, &s, &old, sigs).unwrap_err(),
+        ContractError::VersionBelowCheckpoint {}
+    );
+    let new = with_version(&s.nfinal_state, 124);
+    let sigs = fully_sign(&new, &s.keys);
+    do_checkpoint(&mut deps, &s, &new, sigs).unwrap();
+}
+
+#[test]
+fn checkpoint_disputed() {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let new = with_version(&s.nfinal_state, 124);
+    let sigs = fully_sign(&new, &s.keys);
+    assert_eq!(
+        do_checkpoint(&mut deps, &s, &new, sigs).unwrap_err(),
+        ContractError::ChannelDisputed {}
+    );
+}
+
+#[test]
+fn checkpoint_wrong_sig() {
+    let (s, mut deps) = funded_init();
+    let mut sigs = fully_sign(&s.nfinal_state, &s.keys);
+    sigs.reverse();
+    assert_eq!(
+        do_checkpoint(&mut deps, &s, &s.nfinal_state, sigs).unwrap_err(),
+        ContractError::WrongSignature {}
+    );
+}
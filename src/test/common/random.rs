@@ -19,7 +19,6 @@ use k256::{
     elliptic_curve::sec1::ToEncodedPoint,
 };
 use rand::{CryptoRng, Rng};
-use std::convert::TryInto;
 
 pub type KeyPair = (SigningKey, OffIdentity);
 
@@ -30,7 +29,7 @@ pub fn random_accounts<T: CryptoRng + Rng>(rng: &mut T, num: usize) -> Vec<KeyPa
 pub fn random_account<T: CryptoRng + Rng>(rng: &mut T) -> KeyPair {
     let sk = SigningKey::random(rng);
     let pk = VerifyingKey::from(&sk).to_encoded_point(true);
-    (sk, OffIdentity(pk.as_bytes().try_into().unwrap()))
+    (sk, OffIdentity(pk.as_bytes().into()))
 }
 
 pub fn random_params<T: CryptoRng + Rng>(rng: &mut T) -> (Params, Vec<SigningKey>) {
@@ -42,6 +41,9 @@ pub fn random_params<T: CryptoRng + Rng>(rng: &mut T) -> (Params, Vec<SigningKey
             nonce: random_nonce(rng),
             participants: key_pairs.iter().map(|p| p.1.clone()).collect(),
             dispute_duration: random_dispute_duration(rng),
+            bond: vec![],
+            arbiter: None,
+            expires_at: None,
         },
         key_pairs.iter().map(|p| p.0.clone()).collect(),
     )
@@ -61,6 +63,8 @@ pub fn random_params_state<T: CryptoRng + Rng>(rng: &mut T) -> ((Params, Vec<Sig
             version: random_version(rng),
             balances: random_balances(rng, params.participants.len()),
             finalized: random_finalized(rng),
+            fee: vec![],
+            locks: vec![],
         },
     )
 }
@@ -73,6 +77,7 @@ pub fn random_withdrawal<T: CryptoRng + Rng>(rng: &mut T) -> (Withdrawal, Vec<Si
             channel_id: params.channel_id().unwrap(),
             part: params.participants[index].clone(),
             receiver: cosmwasm_std::Addr::unchecked("ALICE"),
+            fee: vec![],
         },
         sks,
         index,
@@ -12,22 +12,25 @@
 //  See the License for the specific language governing permissions and
 //  limitations under the License.
 
-use super::random::{random_account, random_nonce};
+use super::{
+    crypto::fully_sign,
+    random::{random_account, random_nonce},
+};
 use crate::{
     contract::{execute, instantiate, query},
     crypto::Sig,
     error::ContractError,
     msg::*,
+    storage::{sum_deposits, LIABILITIES},
     types::*,
 };
 use cosmwasm_std::{
     coin,
     testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-    Coin, DepsMut, Env, OwnedDeps, Response,
+    Coin, DepsMut, Env, MessageInfo, Order, OwnedDeps, Response,
 };
-use std::ops::Add;
 
-type Deps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+pub type Deps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
 
 use k256::ecdsa::SigningKey;
 
@@ -53,13 +56,16 @@ pub fn new_setup() -> Setup {
         nonce: random_nonce(&mut rng),
         participants: vec![alice_off.1.clone(), bob_off.1.clone()],
         dispute_duration: 60u64.into(),
+        bond: vec![],
+        arbiter: None,
+        expires_at: None,
     };
     let cid = params.channel_id().unwrap();
     let alloc = vec![
         WrappedBalance::from(vec![coin(2, DENOMS[0]), coin(20, DENOMS[1])]),
         WrappedBalance::from(vec![coin(0, DENOMS[0]), coin(10, DENOMS[1])]),
     ];
-    let outcome = alloc[0].clone().add(&alloc[1]);
+    let outcome = alloc[0].checked_add(&alloc[1]).unwrap();
     Setup {
         keys: vec![alice_off.0, bob_off.0],
         params,
@@ -73,12 +79,16 @@ pub fn new_setup() -> Setup {
             version: 123u64.into(),
             balances: alloc.iter().map(|bals| bals.0.clone()).collect(),
             finalized: true,
+            fee: vec![],
+            locks: vec![],
         },
         nfinal_state: State {
             channel_id: cid.clone(),
             version: 123u64.into(),
             balances: alloc.iter().map(|bals| bals.0.clone()).collect(),
             finalized: false,
+            fee: vec![],
+            locks: vec![],
         },
         alloc,
         outcome,
@@ -86,15 +96,82 @@ pub fn new_setup() -> Setup {
 }
 
 pub fn do_init() -> (Setup, Deps) {
+    do_init_with(InitMsg::default())
+}
+
+pub fn do_init_with(msg: InitMsg) -> (Setup, Deps) {
     let mut deps = mock_dependencies(&[]);
 
     // Instantiate
-    let msg = InitMsg {};
     let info = mock_info("creator_key", &[]);
     instantiate(deps.as_mut(), mock_env(), info, msg).expect("Init failed");
     (new_setup(), deps)
 }
 
+/// Initializes the contract and deposits the allocation of all participants.
+pub fn funded_init() -> (Setup, Deps) {
+    funded_init_with(InitMsg::default())
+}
+
+pub fn funded_init_with(msg: InitMsg) -> (Setup, Deps) {
+    let (s, mut deps) = do_init_with(msg);
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    (s, deps)
+}
+
+/// Like [funded_init] but also disputes the non-final state.
+pub fn disputed_init() -> (Setup, Deps) {
+    disputed_init_with(InitMsg::default())
+}
+
+/// Attaches the [InitMsg::dispute_bond] of `msg` to the dispute.
+pub fn disputed_init_with(msg: InitMsg) -> (Setup, Deps) {
+    let bond = msg.dispute_bond.clone();
+    let (s, mut deps) = funded_init_with(msg);
+    let msg = ExecuteMsg::Dispute {
+        params: s.params.clone(),
+        state: s.nfinal_state.clone(),
+        sigs: fully_sign(&s.nfinal_state, &s.keys),
+        arbiter_sig: None,
+        watcher: None,
+    };
+    let info = mock_info(ALICE, &bond);
+    execute_checked(deps.as_mut(), mock_env(), info, msg).unwrap();
+    (s, deps)
+}
+
+/// Calls [execute] and asserts that the liabilities of the contract match
+/// all deposits afterwards.
+pub fn execute_checked(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let res = execute(deps.branch(), env, info, msg);
+    // Failed executions are reverted on-chain but not in the mock storage.
+    if res.is_ok() {
+        assert_accounting(deps.as_ref());
+    }
+    res
+}
+
+/// Asserts that the liabilities of the contract match all deposits.
+pub fn assert_accounting(deps: cosmwasm_std::Deps) {
+    let liabilities: Vec<Coin> = LIABILITIES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item.unwrap();
+            Coin::new(amount.u128(), String::from_utf8(denom).unwrap())
+        })
+        .collect();
+    assert_eq!(
+        sum_deposits(deps.storage).unwrap(),
+        WrappedBalance::from(liabilities)
+    );
+}
+
 pub fn do_deposit(
     deps: DepsMut,
     fid: &FundingId,
@@ -103,7 +180,7 @@ pub fn do_deposit(
 ) -> Result<Response, ContractError> {
     let msg = ExecuteMsg::Deposit(fid.clone());
     let info = mock_info(who.as_ref(), Vec::<Coin>::from(bals.clone()).as_slice());
-    execute(deps, mock_env(), info, msg)
+    execute_checked(deps, mock_env(), info, msg)
 }
 
 pub fn do_conclude(
@@ -116,9 +193,24 @@ pub fn do_conclude(
         params: params.clone(),
         state: state.clone(),
         sigs: sigs.into(),
+        arbiter_sig: None,
     };
     let info = mock_info(ALICE, &[]);
-    execute(deps, mock_env(), info, msg)
+    execute_checked(deps, mock_env(), info, msg)
+}
+
+pub fn do_conclude_dispute(
+    deps: DepsMut,
+    env: Env,
+    cid: &ChannelId,
+    state: Option<&State>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ConcludeDispute {
+        channel_id: cid.clone(),
+        state: state.cloned(),
+    };
+    let info = mock_info(ALICE, &[]);
+    execute_checked(deps, env, info, msg)
 }
 
 pub fn do_dispute(
@@ -131,9 +223,11 @@ pub fn do_dispute(
         params: params.clone(),
         state: state.clone(),
         sigs: sigs.clone(),
+        arbiter_sig: None,
+        watcher: None,
     };
     let info = mock_info(ALICE, &[]);
-    execute(deps, mock_env(), info, msg)
+    execute_checked(deps, mock_env(), info, msg)
 }
 
 pub fn do_withdraw(
@@ -146,7 +240,7 @@ pub fn do_withdraw(
         sig: sig.clone(),
     };
     let info = mock_info(ALICE, &[]);
-    execute(deps, mock_env(), info, msg)
+    execute_checked(deps, mock_env(), info, msg)
 }
 
 pub fn query_deposit(deps: DepsMut, fid: FundingId) -> WrappedBalance {
@@ -159,7 +253,21 @@ pub fn query_deposit(deps: DepsMut, fid: FundingId) -> WrappedBalance {
     }
 }
 
+pub fn query_channel_status(deps: DepsMut, env: Env, params: &Params) -> ChannelStatusResponse {
+    let msg = QueryMsg::ChannelStatus {
+        params: params.clone(),
+    };
+    let res = query(deps.as_ref(), env, msg).unwrap();
+    cosmwasm_std::from_binary(&res).unwrap()
+}
+
 pub fn advance_time(mut env: Env, by: Seconds) -> Env {
     env.block.time = env.block.time.plus_seconds(by.u64());
     env
 }
+
+/// Returns the amounts of the outcome in the [ConcludeResult] of `res`.
+pub fn outcome(res: &Response) -> Vec<Vec<Coin>> {
+    let result: ConcludeResult = cosmwasm_std::from_binary(res.data.as_ref().unwrap()).unwrap();
+    result.outcome.into_iter().map(|h| h.amount).collect()
+}
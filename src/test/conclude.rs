@@ -13,7 +13,6 @@
 //  limitations under the License.
 
 use crate::{
-    contract::execute,
     crypto::*,
     error::ContractError,
     msg::*,
@@ -21,8 +20,10 @@ use crate::{
         crypto::{fully_sign, sign},
         setup::*,
     },
+    types::WrappedBalance,
 };
 use cosmwasm_std::{
+    coin,
     testing::{mock_env, mock_info},
     Uint64,
 };
@@ -146,10 +147,11 @@ fn conclude_f_wrong_params() {
         params: wrong_params,
         state: s.final_state,
         sigs: vec![], // Use empty sigs since they are not checked.
+        arbiter_sig: None,
     };
     let info = mock_info(ALICE, &[]);
     assert_eq!(
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap_err(),
+        execute_checked(deps.as_mut(), mock_env(), info, msg).unwrap_err(),
         ContractError::WrongChannelId {}
     );
 }
@@ -170,9 +172,7 @@ fn conclude_d_too_early() {
 
 #[test]
 fn conclude_d_after_timeout() {
-    let (s, mut deps) = do_init();
-    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
-    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    let (s, mut deps) = funded_init();
 
     // Omit the `deposit` since disputing an unfunded channel is possible.
     let sigs = fully_sign(&s.nfinal_state, &s.keys);
@@ -184,7 +184,95 @@ fn conclude_d_after_timeout() {
         params: s.params,
         state: s.nfinal_state,
         sigs,
+        arbiter_sig: None,
+    };
+    let info = mock_info(ALICE, &[]);
+    execute_checked(deps.as_mut(), env, info, msg).unwrap();
+}
+
+#[test]
+fn conclude_d_older_version() {
+    let (s, mut deps) = funded_init();
+
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    // Concluding with an older state fails, even after the timeout.
+    let mut old_state = s.nfinal_state.clone();
+    old_state.version = (old_state.version.u64() - 1).into();
+    let env = advance_time(mock_env(), s.params.dispute_duration + Uint64::from(1u64));
+    let msg = ExecuteMsg::Conclude {
+        params: s.params,
+        sigs: fully_sign(&old_state, &s.keys),
+        arbiter_sig: None,
+        state: old_state,
     };
     let info = mock_info(ALICE, &[]);
-    execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        execute_checked(deps.as_mut(), env, info, msg).unwrap_err(),
+        ContractError::DisputeVersionTooLow {}
+    );
+}
+
+#[test]
+fn conclude_dispute_ok() {
+    let (s, mut deps) = funded_init();
+
+    // Register a state that swaps the balances.
+    let mut state = s.nfinal_state.clone();
+    state.balances.reverse();
+    let sigs = fully_sign(&state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &state, &sigs).unwrap();
+
+    let env = advance_time(mock_env(), s.params.dispute_duration + Uint64::from(1u64));
+    do_conclude_dispute(deps.as_mut(), env.clone(), &s.cid, None).unwrap();
+    // The registered state is the outcome.
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[0].clone()), s.alloc[1]);
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[1].clone()), s.alloc[0]);
+    // Concluding twice fails.
+    assert_eq!(
+        do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap_err(),
+        ContractError::AlreadyConcluded {}
+    );
+}
+
+#[test]
+fn conclude_dispute_too_early() {
+    let (s, mut deps) = do_init();
+
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    assert_eq!(
+        do_conclude_dispute(deps.as_mut(), mock_env(), &s.cid, None).unwrap_err(),
+        ContractError::ConcludedTooEarly {}
+    );
+}
+
+#[test]
+fn conclude_dispute_unknown() {
+    let (s, mut deps) = do_init();
+
+    assert_eq!(
+        do_conclude_dispute(deps.as_mut(), mock_env(), &s.cid, None).unwrap_err(),
+        ContractError::UnknownDispute {}
+    );
+}
+
+/// Conclude fails with an error when the outcome sum overflows.
+#[test]
+fn conclude_f_outcome_overflow() {
+    let (s, mut deps) = do_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+
+    let mut state = s.final_state.clone();
+    let bals = WrappedBalance::from(vec![coin(u128::MAX, DENOMS[0])]).0;
+    state.balances = vec![bals.clone(), bals];
+    let sigs = fully_sign(&state, &s.keys);
+    assert_eq!(
+        do_conclude(deps.as_mut(), &s.params, &state, &sigs).unwrap_err(),
+        ContractError::OutcomeOverflow {
+            denom: DENOMS[0].into()
+        }
+    );
 }
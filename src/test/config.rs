@@ -0,0 +1,185 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    contract::query,
+    error::ContractError,
+    msg::*,
+    test::common::{crypto::fully_sign, setup::*},
+    types::*,
+};
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, mock_info},
+    Addr,
+};
+
+const ADMIN: &str = "admin";
+
+fn config_init(
+    funding_caps: Vec<cosmwasm_std::Coin>,
+    denom_caps: Vec<cosmwasm_std::Coin>,
+) -> (Setup, Deps) {
+    do_init_with(InitMsg {
+        admin: Some(ADMIN.into()),
+        allowed_denoms: Some(DENOMS.iter().map(|d| d.to_string()).collect()),
+        funding_caps,
+        denom_caps,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn config_query() {
+    let (_, deps) = config_init(vec![coin(5, DENOMS[0])], vec![]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let ConfigResponse(config) = from_binary(&res).unwrap();
+    assert_eq!(config.admin, Some(Addr::unchecked(ADMIN)));
+    assert_eq!(config.funding_caps, vec![coin(5, DENOMS[0])]);
+    assert!(config.allows_denom(DENOMS[1]));
+    assert!(!config.allows_denom("spam"));
+}
+
+#[test]
+fn deposit_denom_not_allowed() {
+    let (s, mut deps) = config_init(vec![], vec![]);
+    let bals: WrappedBalance = vec![coin(1, DENOMS[0]), coin(1, "spam")].into();
+
+    assert_eq!(
+        do_deposit(deps.as_mut(), &s.fids[0], &bals, ALICE.into()).unwrap_err(),
+        ContractError::DenomNotAllowed {
+            denom: "spam".into()
+        }
+    );
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+}
+
+#[test]
+fn deposit_funding_cap() {
+    let (s, mut deps) = config_init(vec![coin(15, DENOMS[1])], vec![]);
+
+    // Each funding id has its own cap.
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[1], ALICE.into()).unwrap();
+    assert_eq!(
+        do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[1], ALICE.into()).unwrap_err(),
+        ContractError::FundingCapExceeded {
+            denom: DENOMS[1].into()
+        }
+    );
+}
+
+#[test]
+fn deposit_denom_cap() {
+    let (s, mut deps) = config_init(vec![], vec![coin(25, DENOMS[1])]);
+
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[1], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    assert_eq!(
+        do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap_err(),
+        ContractError::DenomCapExceeded {
+            denom: DENOMS[1].into()
+        }
+    );
+}
+
+#[test]
+fn conclude_denom_not_allowed() {
+    let (s, mut deps) = config_init(vec![], vec![]);
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+
+    let mut state = s.final_state.clone();
+    state.balances[1] = WrappedBalance::from(vec![coin(1, "spam")]).0;
+    let sigs = fully_sign(&state, &s.keys);
+    assert_eq!(
+        do_conclude(deps.as_mut(), &s.params, &state, &sigs).unwrap_err(),
+        ContractError::DenomNotAllowed {
+            denom: "spam".into()
+        }
+    );
+}
+
+#[test]
+fn update_config() {
+    let (s, mut deps) = config_init(vec![], vec![]);
+    let msg = ExecuteMsg::UpdateConfig {
+        allowed_denoms: None,
+        funding_caps: vec![],
+        denom_caps: vec![],
+    };
+    assert_eq!(
+        execute_checked(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ALICE, &[]),
+            msg.clone()
+        )
+        .unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+
+    // All denoms are allowed now.
+    let bals: WrappedBalance = vec![coin(1, "spam")].into();
+    do_deposit(deps.as_mut(), &s.fids[0], &bals, ALICE.into()).unwrap();
+}
+
+/// Each admin message only replaces its own part of the config.
+#[test]
+fn update_config_keeps_others() {
+    let (_, mut deps) = config_init(vec![coin(5, DENOMS[0])], vec![coin(7, DENOMS[1])]);
+    let load = |deps: &Deps| {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let ConfigResponse(config) = from_binary(&res).unwrap();
+        config
+    };
+    let before = load(&deps);
+
+    let limits = ChannelLimits {
+        max_participants: 3,
+        ..Default::default()
+    };
+    let msgs = [
+        ExecuteMsg::SetChannelLimits {
+            limits: limits.clone(),
+        },
+        ExecuteMsg::SetDisputeBond {
+            dispute_bond: vec![coin(1, DENOMS[0])],
+        },
+    ];
+    for msg in msgs {
+        execute_checked(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+    }
+    let after = load(&deps);
+    assert_eq!(
+        Config {
+            limits,
+            dispute_bond: vec![coin(1, DENOMS[0])],
+            ..before
+        },
+        after
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        allowed_denoms: None,
+        funding_caps: vec![],
+        denom_caps: vec![],
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+    let updated = load(&deps);
+    assert_eq!(updated.limits, after.limits);
+    assert_eq!(updated.dispute_bond, after.dispute_bond);
+}
@@ -12,7 +12,7 @@
 //  See the License for the specific language governing permissions and
 //  limitations under the License.
 
-use crate::{test::common::setup::*, types::WrappedBalance};
+use crate::{error::ContractError, test::common::setup::*, types::WrappedBalance};
 use cosmwasm_std::coin;
 
 #[test]
@@ -42,13 +42,31 @@ fn deposit_twice() {
 }
 
 #[test]
-#[should_panic(expected = "attempt to add with overflow")]
 fn deposit_overflow() {
     let (s, mut deps) = do_init();
 
-    let bals: WrappedBalance = vec![coin(std::u128::MAX - 10, DENOMS[0])].into();
+    let bals: WrappedBalance = vec![coin(u128::MAX - 10, DENOMS[0])].into();
     // Normal
     do_deposit(deps.as_mut(), &s.fids[0], &bals, ALICE.into()).unwrap();
     // Overflow
-    do_deposit(deps.as_mut(), &s.fids[0], &bals, ALICE.into()).expect_err("Should overflow");
+    assert_eq!(
+        do_deposit(deps.as_mut(), &s.fids[0], &bals, ALICE.into()).unwrap_err(),
+        ContractError::BalanceOverflow {
+            denom: DENOMS[0].into()
+        }
+    );
+}
+
+/// Deposits of IBC vouchers, as received through an ICS-20 transfer,
+/// are credited like native funds.
+#[test]
+fn deposit_ibc_voucher() {
+    let (s, mut deps) = do_init();
+    let voucher = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+    let bals: WrappedBalance = vec![coin(100, voucher)].into();
+
+    do_deposit(deps.as_mut(), &s.fids[0], &bals, "osmo1hooks".into()).unwrap();
+
+    let deposited = query_deposit(deps.as_mut(), s.fids[0].clone());
+    assert_eq!(deposited, bals);
 }
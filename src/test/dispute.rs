@@ -13,7 +13,6 @@
 //  limitations under the License.
 
 use crate::{
-    contract::execute,
     crypto::Sig,
     error::ContractError,
     msg::ExecuteMsg,
@@ -49,16 +48,18 @@ fn dispute_final() {
     );
 }
 
-/// Dispute panics when the [Params::dispute_duration] overflows.
+/// Dispute rejects a [Params::dispute_duration] that would overflow.
 #[test]
-#[should_panic(expected = "attempt to multiply with overflow")]
 fn dispute_dispute_duration_overflow() {
     let (mut s, mut deps) = do_init();
     s.params.dispute_duration = Uint64::from(Seconds::MAX.u64() - 1);
     s.nfinal_state.channel_id = s.params.channel_id().unwrap();
     let sigs = fully_sign(&s.nfinal_state, &s.keys);
 
-    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap_err(),
+        ContractError::InvalidDisputeDuration {}
+    );
 }
 
 /// Dispute fails with an incorrect number of signatures.
@@ -231,11 +232,13 @@ fn dispute_timeout() {
         params: s.params.clone(),
         state,
         sigs,
+        arbiter_sig: None,
+        watcher: None,
     };
     let info = mock_info(ALICE, &[]);
     let env = advance_time(mock_env(), s.params.dispute_duration + Uint64::from(1u64));
     assert_eq!(
-        execute(deps.as_mut(), env, info, msg).unwrap_err(),
+        execute_checked(deps.as_mut(), env, info, msg).unwrap_err(),
         ContractError::DisputeTimedOut {}
     );
 }
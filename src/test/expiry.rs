@@ -0,0 +1,270 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    coin, coins,
+    testing::{mock_env, mock_info},
+    Addr, Coin, Env, Response,
+};
+
+const LIFETIME: u64 = 1000;
+
+/// Returns a funded channel whose [Params] expire after [LIFETIME].
+fn expiring_init(msg: InitMsg) -> (Setup, Deps) {
+    let (mut s, mut deps) = do_init_with(msg);
+    s.params.expires_at = Some(mock_env().block.time.plus_seconds(LIFETIME));
+    s.cid = s.params.channel_id().unwrap();
+    s.fids = s
+        .params
+        .participants
+        .iter()
+        .map(|part| calc_funding_id(&s.cid, part).unwrap())
+        .collect();
+    s.final_state.channel_id = s.cid.clone();
+    s.nfinal_state.channel_id = s.cid.clone();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    (s, deps)
+}
+
+fn expired() -> Env {
+    advance_time(mock_env(), LIFETIME.into())
+}
+
+fn do_conclude_expired(
+    deps: &mut Deps,
+    env: Env,
+    s: &Setup,
+    state: Option<&State>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ConcludeExpired {
+        params: s.params.clone(),
+        state: state.cloned(),
+    };
+    execute_checked(deps.as_mut(), env, mock_info(BOB, &[]), msg)
+}
+
+/// Returns a newer state of `s` in which Alice pays 5 ATOM to Bob.
+fn newer_state(s: &Setup) -> State {
+    let mut state = s.nfinal_state.clone();
+    state.version = 124u64.into();
+    state.balances[0] = cw0::NativeBalance(vec![coin(15, DENOMS[1]), coin(2, DENOMS[0])]);
+    state.balances[1] = cw0::NativeBalance(coins(15, DENOMS[1]));
+    state
+}
+
+fn checkpoint(deps: &mut Deps, s: &Setup, state: &State) {
+    let msg = ExecuteMsg::Checkpoint {
+        params: s.params.clone(),
+        state: state.clone(),
+        sigs: fully_sign(state, &s.keys),
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap();
+}
+
+/// Channels without any state on-chain pay the deposits back.
+#[test]
+fn expiry_initial_allocation() {
+    let (s, mut deps) = expiring_init(InitMsg::default());
+    let early = advance_time(mock_env(), (LIFETIME - 1).into());
+    assert_eq!(
+        do_conclude_expired(&mut deps, early, &s, None).unwrap_err(),
+        ContractError::NotExpired {}
+    );
+
+    let res = do_conclude_expired(&mut deps, expired(), &s, None).unwrap();
+    let alloc: Vec<Vec<Coin>> = s.alloc.iter().map(|bals| bals.clone().into()).collect();
+    assert_eq!(outcome(&res), alloc);
+    let status = query_channel_status(deps.as_mut(), expired(), &s.params);
+    assert_eq!(status.phase, ChannelPhase::Concluded);
+    assert_eq!(
+        do_conclude_expired(&mut deps, expired(), &s, None).unwrap_err(),
+        ContractError::AlreadyConcluded {}
+    );
+}
+
+#[test]
+fn expiry_checkpoint() {
+    let (s, mut deps) = expiring_init(InitMsg::default());
+    let state = newer_state(&s);
+    checkpoint(&mut deps, &s, &state);
+
+    let res = do_conclude_expired(&mut deps, expired(), &s, None).unwrap();
+    assert_eq!(
+        outcome(&res),
+        vec![state.balances[0].0.clone(), state.balances[1].0.clone()]
+    );
+}
+
+/// Compactly stored checkpoints need their state as preimage.
+#[test]
+fn expiry_checkpoint_compact() {
+    let msg = InitMsg {
+        compact_disputes: true,
+        ..Default::default()
+    };
+    let (s, mut deps) = expiring_init(msg);
+    let state = newer_state(&s);
+    checkpoint(&mut deps, &s, &state);
+
+    assert_eq!(
+        do_conclude_expired(&mut deps, expired(), &s, None).unwrap_err(),
+        ContractError::MissingPreimage {}
+    );
+    do_conclude_expired(&mut deps, expired(), &s, Some(&state)).unwrap();
+}
+
+fn dispute_msg(s: &Setup, state: &State) -> ExecuteMsg {
+    ExecuteMsg::Dispute {
+        params: s.params.clone(),
+        state: state.clone(),
+        sigs: fully_sign(state, &s.keys),
+        arbiter_sig: None,
+        watcher: None,
+    }
+}
+
+/// Disputes opened before the expiry can be refuted until their timeout.
+#[test]
+fn expiry_running_dispute() {
+    let (s, mut deps) = expiring_init(InitMsg::default());
+    let env = advance_time(mock_env(), (LIFETIME - 10).into());
+    let msg = dispute_msg(&s, &s.nfinal_state);
+    execute_checked(deps.as_mut(), env, mock_info(ALICE, &[]), msg).unwrap();
+
+    assert_eq!(
+        do_conclude_expired(&mut deps, expired(), &s, None).unwrap_err(),
+        ContractError::ConcludedTooEarly {}
+    );
+    let state = newer_state(&s);
+    let msg = dispute_msg(&s, &state);
+    execute_checked(deps.as_mut(), expired(), mock_info(BOB, &[]), msg).unwrap();
+
+    let timeout = advance_time(mock_env(), (LIFETIME - 10).into());
+    let timeout = advance_time(timeout, s.params.dispute_duration);
+    let res = do_conclude_expired(&mut deps, timeout.clone(), &s, None).unwrap();
+    assert_eq!(
+        outcome(&res),
+        vec![state.balances[0].0.clone(), state.balances[1].0.clone()]
+    );
+    let status = query_channel_status(deps.as_mut(), timeout, &s.params);
+    assert_eq!(status.version, Some(state.version));
+}
+
+/// A stale state can not be disputed and concluded right after the expiry.
+#[test]
+fn expiry_stale_dispute() {
+    let (s, mut deps) = expiring_init(InitMsg::default());
+    // Newer states were only exchanged off-chain.
+    let state = &s.nfinal_state;
+    let msg = ExecuteMsg::Batch(vec![
+        Operation::Dispute {
+            params: s.params.clone(),
+            state: state.clone(),
+            sigs: fully_sign(state, &s.keys),
+            arbiter_sig: None,
+            watcher: None,
+            bond: vec![],
+        },
+        Operation::ConcludeExpired {
+            params: s.params.clone(),
+            state: None,
+        },
+    ]);
+    assert_eq!(
+        execute_checked(deps.as_mut(), expired(), mock_info(ALICE, &[]), msg).unwrap_err(),
+        ContractError::ChannelExpired {}
+    );
+    let msg = ExecuteMsg::Checkpoint {
+        params: s.params.clone(),
+        state: state.clone(),
+        sigs: fully_sign(state, &s.keys),
+    };
+    assert_eq!(
+        execute_checked(deps.as_mut(), expired(), mock_info(ALICE, &[]), msg).unwrap_err(),
+        ContractError::ChannelExpired {}
+    );
+
+    let res = do_conclude_expired(&mut deps, expired(), &s, None).unwrap();
+    let alloc: Vec<Vec<Coin>> = s.alloc.iter().map(|bals| bals.clone().into()).collect();
+    assert_eq!(outcome(&res), alloc);
+}
+
+/// Fully signed final states can be concluded or settled after the expiry.
+#[test]
+fn expiry_cooperative_close() {
+    let (s, _) = expiring_init(InitMsg::default());
+    let state = &s.final_state;
+    let sigs = fully_sign(state, &s.keys);
+    let withdrawals = (0..2)
+        .map(|i| {
+            let withdrawal = Withdrawal {
+                channel_id: s.cid.clone(),
+                part: s.params.participants[i].clone(),
+                receiver: Addr::unchecked(ALICE),
+                fee: vec![],
+            };
+            let sig = sign(&withdrawal, &s.keys[i]);
+            SignedWithdrawal { withdrawal, sig }
+        })
+        .collect();
+    let msgs = [
+        ExecuteMsg::Conclude {
+            params: s.params.clone(),
+            state: state.clone(),
+            sigs: sigs.clone(),
+            arbiter_sig: None,
+        },
+        ExecuteMsg::Settle {
+            params: s.params.clone(),
+            state: state.clone(),
+            sigs,
+            withdrawals,
+        },
+    ];
+    for msg in msgs {
+        let (_, mut deps) = do_init();
+        do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+        do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+        execute_checked(deps.as_mut(), expired(), mock_info(ALICE, &[]), msg).unwrap();
+        let status = query_channel_status(deps.as_mut(), expired(), &s.params);
+        assert_ne!(status.phase, ChannelPhase::Open);
+    }
+}
+
+#[test]
+fn expiry_not_set() {
+    let (s, mut deps) = do_init();
+    assert_eq!(
+        do_conclude_expired(&mut deps, expired(), &s, None).unwrap_err(),
+        ContractError::NoExpiry {}
+    );
+}
+
+/// Channels without expiry keep their encoding and channel id.
+#[test]
+fn expiry_not_encoded() {
+    let s = new_setup();
+    let encoded = encode_obj(&s.params).unwrap();
+    assert!(!String::from_utf8(encoded).unwrap().contains("expires_at"));
+}
@@ -0,0 +1,136 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_env, mock_info},
+    Env, Response, Timestamp,
+};
+
+fn do_extend(
+    deps: &mut Deps,
+    env: Env,
+    s: &Setup,
+    timeout: Timestamp,
+    nonce: u64,
+) -> Result<Response, ContractError> {
+    let extension = DisputeExtension {
+        channel_id: s.cid.clone(),
+        version: s.nfinal_state.version,
+        timeout,
+        nonce: nonce.into(),
+    };
+    let msg = ExecuteMsg::ExtendDispute {
+        params: s.params.clone(),
+        extension: extension.clone(),
+        sigs: s.keys.iter().map(|key| sign(&extension, key)).collect(),
+    };
+    execute_checked(deps.as_mut(), env, mock_info(ALICE, &[]), msg)
+}
+
+#[test]
+fn extend_ok() {
+    let (s, mut deps) = disputed_init();
+    let timeout = mock_env().block.time.plus_seconds(120);
+    let res = do_extend(&mut deps, mock_env(), &s, timeout, 1).unwrap();
+    let result: DisputeResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.timeout, timeout);
+
+    // The dispute can not be concluded after the original timeout.
+    let env = advance_time(mock_env(), 60u64.into());
+    assert_eq!(
+        do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap_err(),
+        ContractError::ConcludedTooEarly {}
+    );
+    let env = advance_time(mock_env(), 120u64.into());
+    do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap();
+}
+
+#[test]
+fn extend_invalid_timeout() {
+    let (s, mut deps) = disputed_init();
+    let now = mock_env().block.time;
+    let max = 365 * 24 * 60 * 60;
+    for timeout in [now.plus_seconds(60), now.plus_seconds(max + 1)] {
+        assert_eq!(
+            do_extend(&mut deps, mock_env(), &s, timeout, 1).unwrap_err(),
+            ContractError::InvalidTimeout {}
+        );
+    }
+}
+
+#[test]
+fn extend_timed_out() {
+    let (s, mut deps) = disputed_init();
+    let env = advance_time(mock_env(), 60u64.into());
+    let timeout = env.block.time.plus_seconds(60);
+    assert_eq!(
+        do_extend(&mut deps, env, &s, timeout, 1).unwrap_err(),
+        ContractError::DisputeTimedOut {}
+    );
+}
+
+/// Extensions name the registered version so that they can not be used on
+/// disputes of other versions.
+#[test]
+fn extend_wrong_version() {
+    let (s, mut deps) = disputed_init();
+    let mut state = s.nfinal_state.clone();
+    state.version = 124u64.into();
+    let sigs = fully_sign(&state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &state, &sigs).unwrap();
+
+    let timeout = mock_env().block.time.plus_seconds(120);
+    assert_eq!(
+        do_extend(&mut deps, mock_env(), &s, timeout, 1).unwrap_err(),
+        ContractError::WrongDisputeVersion {}
+    );
+}
+
+/// Applied extensions can not be replayed on a later dispute of the same
+/// version.
+#[test]
+fn extend_replay() {
+    let (s, mut deps) = disputed_init();
+    let timeout = mock_env().block.time.plus_seconds(120);
+    do_extend(&mut deps, mock_env(), &s, timeout, 1).unwrap();
+
+    let cancellation = DisputeCancellation {
+        channel_id: s.cid.clone(),
+        version: s.nfinal_state.version,
+        nonce: 2u64.into(),
+    };
+    let msg = ExecuteMsg::CancelDispute {
+        params: s.params.clone(),
+        cancellation: cancellation.clone(),
+        sigs: s.keys.iter().map(|key| sign(&cancellation, key)).collect(),
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    assert_eq!(
+        do_extend(&mut deps, mock_env(), &s, timeout, 1).unwrap_err(),
+        ContractError::NonceUsed {}
+    );
+}
@@ -0,0 +1,180 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, mock_info},
+    Addr, BankMsg, Coin, CosmosMsg, Env, Response, Uint64,
+};
+
+const RELAYER: &str = "relayer";
+
+fn relay(deps: &mut Deps, env: Env, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    execute_checked(deps.as_mut(), env, mock_info(RELAYER, &[]), msg)
+}
+
+fn sends(res: &Response) -> Vec<CosmosMsg> {
+    res.messages.iter().map(|m| m.msg.clone()).collect()
+}
+
+fn bank_send(to: &str, amount: &WrappedBalance) -> CosmosMsg {
+    CosmosMsg::Bank(BankMsg::Send {
+        to_address: to.into(),
+        amount: amount.clone().into(),
+    })
+}
+
+/// Returns the final state of `s` with a fee that is taken from Alice.
+fn state_with_fee(s: &Setup, fee: &WrappedBalance) -> State {
+    let mut state = s.final_state.clone();
+    state.balances[0] = s.alloc[0].checked_sub(fee).unwrap().0;
+    state.fee = fee.clone().into();
+    state
+}
+
+/// Empty fees are not encoded so that existing signatures stay valid.
+#[test]
+fn fee_empty_not_encoded() {
+    let s = new_setup();
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    for encoded in [
+        encode_obj(&withdrawal).unwrap(),
+        encode_obj(&s.final_state).unwrap(),
+    ] {
+        assert!(!String::from_utf8(encoded).unwrap().contains("fee"));
+    }
+}
+
+#[test]
+fn fee_withdraw() {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+
+    let fee = WrappedBalance::from(vec![coin(1, DENOMS[1])]);
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: Addr::unchecked(ALICE),
+        fee: fee.clone().into(),
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    let msg = ExecuteMsg::Withdraw { withdrawal, sig };
+    let res = relay(&mut deps, mock_env(), msg).unwrap();
+
+    let rest = s.alloc[0].checked_sub(&fee).unwrap();
+    assert_eq!(
+        sends(&res),
+        vec![bank_send(ALICE, &rest), bank_send(RELAYER, &fee)]
+    );
+    let result: WithdrawResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.amount, Vec::<Coin>::from(rest));
+    assert_eq!(result.fee, Vec::<Coin>::from(fee));
+}
+
+/// The fee of a withdrawal can not exceed the outcome.
+#[test]
+fn fee_withdraw_too_high() {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[1].clone(),
+        receiver: Addr::unchecked(BOB),
+        fee: vec![coin(11, DENOMS[1])],
+    };
+    let sig = sign(&withdrawal, &s.keys[1]);
+    let msg = ExecuteMsg::Withdraw { withdrawal, sig };
+    assert_eq!(
+        relay(&mut deps, mock_env(), msg).unwrap_err(),
+        ContractError::BalanceUnderflow {
+            denom: DENOMS[1].into()
+        }
+    );
+}
+
+#[test]
+fn fee_conclude() {
+    let (s, mut deps) = funded_init();
+    let fee = WrappedBalance::from(vec![coin(2, DENOMS[1])]);
+    let state = state_with_fee(&s, &fee);
+
+    let msg = ExecuteMsg::Conclude {
+        params: s.params.clone(),
+        state: state.clone(),
+        sigs: fully_sign(&state, &s.keys),
+        arbiter_sig: None,
+    };
+    let res = relay(&mut deps, mock_env(), msg.clone()).unwrap();
+    assert_eq!(sends(&res), vec![bank_send(RELAYER, &fee)]);
+    let result: ConcludeResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.fee, Vec::<Coin>::from(fee));
+    assert_eq!(
+        query_deposit(deps.as_mut(), s.fids[0].clone()).0,
+        state.balances[0]
+    );
+
+    // The fee is only paid once.
+    let res = relay(&mut deps, mock_env(), msg).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+/// The fee of a state must be covered by the deposits.
+#[test]
+fn fee_conclude_insufficient_deposits() {
+    let (s, mut deps) = funded_init();
+    let mut state = s.final_state.clone();
+    state.fee = vec![coin(1, DENOMS[0])];
+
+    let sigs = fully_sign(&state, &s.keys);
+    assert_eq!(
+        do_conclude(deps.as_mut(), &s.params, &state, &sigs).unwrap_err(),
+        ContractError::InsufficientDeposits {}
+    );
+}
+
+/// Concluding a dispute pays the fee of the registered state.
+#[test]
+fn fee_conclude_dispute() {
+    let (s, mut deps) = funded_init();
+    let fee = WrappedBalance::from(vec![coin(2, DENOMS[1])]);
+    let mut state = state_with_fee(&s, &fee);
+    state.finalized = false;
+    let sigs = fully_sign(&state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &state, &sigs).unwrap();
+
+    let env = advance_time(mock_env(), s.params.dispute_duration + Uint64::from(1u64));
+    let msg = ExecuteMsg::ConcludeDispute {
+        channel_id: s.cid.clone(),
+        state: None,
+    };
+    let res = relay(&mut deps, env, msg).unwrap();
+    assert_eq!(sends(&res), vec![bank_send(RELAYER, &fee)]);
+}
@@ -0,0 +1,155 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    crypto::hash_bytes,
+    error::ContractError,
+    msg::*,
+    test::common::{crypto::fully_sign, setup::*},
+    types::*,
+};
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, mock_info},
+    Binary, Coin, Env, Response,
+};
+
+const PREIMAGE: &[u8] = b"secret";
+
+/// Returns the final state of `s` where Alice locks `amount` for Bob.
+fn state_with_lock(s: &Setup, amount: &WrappedBalance) -> State {
+    let mut state = s.final_state.clone();
+    state.balances[0] = s.alloc[0].checked_sub(amount).unwrap().0;
+    state.locks = vec![Lock {
+        hash: hash_bytes(PREIMAGE),
+        timeout: mock_env().block.time.plus_seconds(100),
+        amount: amount.clone().into(),
+        sender: 0,
+        receiver: 1,
+    }];
+    state
+}
+
+fn reveal(deps: &mut Deps, env: Env, preimage: &[u8]) -> RevealResult {
+    let msg = ExecuteMsg::RevealPreimage {
+        preimage: Binary::from(preimage),
+    };
+    let res = execute_checked(deps.as_mut(), env, mock_info(BOB, &[]), msg).unwrap();
+    from_binary(&res.data.unwrap()).unwrap()
+}
+
+fn conclude_at(
+    deps: &mut Deps,
+    env: Env,
+    s: &Setup,
+    state: &State,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Conclude {
+        params: s.params.clone(),
+        state: state.clone(),
+        sigs: fully_sign(state, &s.keys),
+        arbiter_sig: None,
+    };
+    execute_checked(deps.as_mut(), env, mock_info(ALICE, &[]), msg)
+}
+
+/// Empty locks are not encoded so that existing signatures stay valid.
+#[test]
+fn htlc_empty_not_encoded() {
+    let s = new_setup();
+    let encoded = encode_obj(&s.final_state).unwrap();
+    assert!(!String::from_utf8(encoded).unwrap().contains("locks"));
+}
+
+#[test]
+fn htlc_reveal_keeps_first() {
+    let (_, mut deps) = do_init();
+    let first = reveal(&mut deps, mock_env(), PREIMAGE);
+    assert_eq!(first.hash, hash_bytes(PREIMAGE));
+    assert_eq!(first.revealed_at, mock_env().block.time);
+
+    let later = advance_time(mock_env(), 10u64.into());
+    assert_eq!(reveal(&mut deps, later, PREIMAGE), first);
+}
+
+/// A lock goes to the receiver if its preimage was revealed in time.
+#[test]
+fn htlc_revealed() {
+    let (s, mut deps) = funded_init();
+    let amount = WrappedBalance::from(vec![coin(5, DENOMS[1])]);
+    let state = state_with_lock(&s, &amount);
+    reveal(&mut deps, mock_env(), PREIMAGE);
+
+    let res = conclude_at(&mut deps, mock_env(), &s, &state).unwrap();
+    let bob = s.alloc[1].checked_add(&amount).unwrap();
+    assert_eq!(
+        outcome(&res),
+        vec![state.balances[0].0.clone(), bob.clone().into()]
+    );
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[1].clone()), bob);
+}
+
+/// A lock goes back to the sender once it timed out.
+#[test]
+fn htlc_timed_out() {
+    let (s, mut deps) = funded_init();
+    let amount = WrappedBalance::from(vec![coin(5, DENOMS[1])]);
+    let state = state_with_lock(&s, &amount);
+
+    // The receiver can still reveal the preimage.
+    assert_eq!(
+        conclude_at(&mut deps, mock_env(), &s, &state).unwrap_err(),
+        ContractError::LockPending {}
+    );
+    // Reveals after the timeout do not count.
+    let after = advance_time(mock_env(), 100u64.into());
+    reveal(&mut deps, after.clone(), PREIMAGE);
+
+    let res = conclude_at(&mut deps, after, &s, &state).unwrap();
+    assert_eq!(
+        outcome(&res),
+        vec![
+            Vec::<Coin>::from(s.alloc[0].clone()),
+            s.alloc[1].clone().into()
+        ]
+    );
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[0].clone()), s.alloc[0]);
+}
+
+/// Locked amounts count towards the deposits of the channel.
+#[test]
+fn htlc_insufficient_deposits() {
+    let (s, mut deps) = funded_init();
+    let mut state = s.final_state.clone();
+    state.locks = state_with_lock(&s, &vec![coin(1, DENOMS[1])].into()).locks;
+    reveal(&mut deps, mock_env(), PREIMAGE);
+
+    assert_eq!(
+        conclude_at(&mut deps, mock_env(), &s, &state).unwrap_err(),
+        ContractError::InsufficientDeposits {}
+    );
+}
+
+#[test]
+fn htlc_invalid_lock() {
+    let (s, mut deps) = funded_init();
+    let amount = WrappedBalance::from(vec![coin(5, DENOMS[1])]);
+    let mut state = state_with_lock(&s, &amount);
+    state.locks[0].receiver = 2;
+
+    assert_eq!(
+        conclude_at(&mut deps, mock_env(), &s, &state).unwrap_err(),
+        ContractError::InvalidLock {}
+    );
+}
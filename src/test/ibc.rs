@@ -0,0 +1,138 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{error::ContractError, msg::*, storage::LIABILITIES, test::common::setup::*, types::*};
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, mock_info},
+    Addr, BankMsg, Binary, CosmosMsg, Response, StdError,
+};
+
+/// Sender of the hook calls, derived by the middleware from the channel and
+/// the original sender.
+const HOOKS: &str = "osmo1hooks";
+const VOUCHER: &str = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+fn do_ibc_deposit(
+    deps: &mut Deps,
+    funding_id: &str,
+    bals: &WrappedBalance,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::IbcDeposit {
+        funding_id: funding_id.into(),
+        refund_to: ALICE.into(),
+    };
+    let info = mock_info(HOOKS, &Vec::<cosmwasm_std::Coin>::from(bals.clone()));
+    execute_checked(deps.as_mut(), mock_env(), info, msg)
+}
+
+fn ibc_result(res: &Response) -> IbcDepositResult {
+    from_binary(res.data.as_ref().unwrap()).unwrap()
+}
+
+/// Asserts that `res` refunds `bals` to Alice.
+fn assert_refunded(res: &Response, bals: &WrappedBalance, err: ContractError) {
+    let refund = vec![CosmosMsg::Bank(BankMsg::Send {
+        to_address: ALICE.into(),
+        amount: bals.clone().into(),
+    })];
+    assert_eq!(
+        res.messages
+            .iter()
+            .map(|m| m.msg.clone())
+            .collect::<Vec<_>>(),
+        refund
+    );
+    assert_eq!(
+        ibc_result(res),
+        IbcDepositResult::Refunded {
+            receiver: Addr::unchecked(ALICE),
+            amount: bals.clone().into(),
+            reason: err.to_string(),
+        }
+    );
+}
+
+#[test]
+fn ibc_deposit() {
+    let (s, mut deps) = do_init();
+    let bals: WrappedBalance = vec![coin(100, VOUCHER)].into();
+
+    let res = do_ibc_deposit(&mut deps, &s.fids[0].0.to_base64(), &bals).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        ibc_result(&res),
+        IbcDepositResult::Deposited(DepositResult {
+            funding_id: s.fids[0].clone(),
+            holding: bals.clone().into(),
+        })
+    );
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[0].clone()), bals);
+}
+
+/// Funding ids that can not be parsed lead to a refund.
+#[test]
+fn ibc_deposit_invalid_funding_id() {
+    let (s, mut deps) = do_init();
+    let bals: WrappedBalance = vec![coin(100, VOUCHER)].into();
+    let short = &s.fids[0].as_slice()[1..];
+
+    for fid in ["no base64!".to_string(), Binary::from(short).to_base64()] {
+        let res = do_ibc_deposit(&mut deps, &fid, &bals).unwrap();
+        assert_refunded(&res, &bals, ContractError::InvalidFundingId {});
+    }
+    assert!(LIABILITIES
+        .may_load(deps.as_ref().storage, VOUCHER)
+        .unwrap()
+        .is_none());
+}
+
+/// Rejected deposits are refunded without leaving any trace in the storage.
+#[test]
+fn ibc_deposit_rejected() {
+    let (s, mut deps) = do_init_with(InitMsg {
+        denom_caps: vec![coin(150, VOUCHER)],
+        ..Default::default()
+    });
+    let bals: WrappedBalance = vec![coin(100, VOUCHER)].into();
+    let fid = s.fids[0].0.to_base64();
+    do_ibc_deposit(&mut deps, &fid, &bals).unwrap();
+
+    let res = do_ibc_deposit(&mut deps, &fid, &bals).unwrap();
+    let err = ContractError::DenomCapExceeded {
+        denom: VOUCHER.into(),
+    };
+    assert_refunded(&res, &bals, err);
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[0].clone()), bals);
+    assert_eq!(
+        LIABILITIES.load(deps.as_ref().storage, VOUCHER).unwrap(),
+        100u128.into()
+    );
+}
+
+/// Without a valid refund address the call fails, which makes the middleware
+/// refund the transfer on the sending chain.
+#[test]
+fn ibc_deposit_invalid_refund_address() {
+    let (s, mut deps) = do_init();
+    let msg = ExecuteMsg::IbcDeposit {
+        funding_id: s.fids[0].0.to_base64(),
+        refund_to: "".into(),
+    };
+    let info = mock_info(HOOKS, &[coin(100, VOUCHER)]);
+    assert!(matches!(
+        execute_checked(deps.as_mut(), mock_env(), info, msg).unwrap_err(),
+        ContractError::Std(StdError::GenericErr { .. })
+    ));
+}
@@ -1,13 +1,57 @@
+#[cfg(test)]
+pub mod admin;
+#[cfg(test)]
+pub mod arbiter;
+#[cfg(test)]
+pub mod batch;
+#[cfg(test)]
+pub mod bond;
+#[cfg(test)]
+pub mod cancel;
+#[cfg(test)]
+pub mod checkpoint;
 pub mod common;
 #[cfg(test)]
 pub mod conclude;
 #[cfg(test)]
+pub mod config;
+#[cfg(test)]
 pub mod crypto;
 #[cfg(test)]
 pub mod deposit;
 #[cfg(test)]
 pub mod dispute;
 #[cfg(test)]
+pub mod expiry;
+#[cfg(test)]
+pub mod extend;
+#[cfg(test)]
+pub mod fee;
+#[cfg(test)]
+pub mod htlc;
+#[cfg(test)]
+pub mod ibc;
+#[cfg(test)]
+pub mod novation;
+#[cfg(test)]
+pub mod params;
+#[cfg(test)]
+pub mod result;
+#[cfg(test)]
+pub mod rollover;
+#[cfg(test)]
+pub mod settle;
+#[cfg(test)]
+pub mod solvency;
+#[cfg(test)]
+pub mod splice;
+#[cfg(test)]
+pub mod status;
+#[cfg(test)]
+pub mod storage;
+#[cfg(test)]
 pub mod types;
 #[cfg(test)]
+pub mod watcher;
+#[cfg(test)]
 pub mod withdraw;
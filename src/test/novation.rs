@@ -0,0 +1,234 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    crypto::{OffIdentity, Sig},
+    error::ContractError,
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        random::{random_account, random_nonce},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_env, mock_info},
+    Addr, Response,
+};
+use k256::ecdsa::SigningKey;
+
+fn do_novate(
+    deps: &mut Deps,
+    s: &Setup,
+    novation: &Novation,
+    keys: &[SigningKey],
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Novate {
+        params: s.params.clone(),
+        novation: novation.clone(),
+        sigs: keys.iter().map(|key| sign(novation, key)).collect(),
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg)
+}
+
+/// Replaces the identity of Alice and returns her new key.
+fn novate_alice(deps: &mut Deps, s: &Setup) -> (SigningKey, OffIdentity) {
+    let (key, identity) = random_account(&mut rand::thread_rng());
+    let novation = Novation {
+        channel_id: s.cid.clone(),
+        part: 0,
+        old: s.params.participants[0].clone(),
+        new: identity.clone(),
+        nonce: 1u64.into(),
+    };
+    let res = do_novate(deps, s, &novation, &s.keys).unwrap();
+    let result: NovationResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.identity, identity);
+    (key, identity)
+}
+
+fn withdraw_alice(deps: &mut Deps, s: &Setup, key: &SigningKey) -> Result<Response, ContractError> {
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig: Sig = sign(&withdrawal, key);
+    do_withdraw(deps.as_mut(), &withdrawal, &sig)
+}
+
+#[test]
+fn novation_ok() {
+    let (s, mut deps) = funded_init();
+    let (key, _) = novate_alice(&mut deps, &s);
+    let keys = vec![key.clone(), s.keys[1].clone()];
+
+    // States must be signed by the new identity.
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap_err(),
+        ContractError::WrongSignature {}
+    );
+    let sigs = fully_sign(&s.final_state, &keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+
+    // So must withdrawals, the funding id stays the same.
+    assert_eq!(
+        withdraw_alice(&mut deps, &s, &s.keys[0]).unwrap_err(),
+        ContractError::WrongSignature {}
+    );
+    withdraw_alice(&mut deps, &s, &key).unwrap();
+}
+
+/// Further novations must be signed by the new identity.
+#[test]
+fn novation_twice() {
+    let (s, mut deps) = funded_init();
+    let (key, identity) = novate_alice(&mut deps, &s);
+    let novation = Novation {
+        channel_id: s.cid.clone(),
+        part: 0,
+        old: identity,
+        new: random_account(&mut rand::thread_rng()).1,
+        nonce: 2u64.into(),
+    };
+    assert_eq!(
+        do_novate(&mut deps, &s, &novation, &s.keys).unwrap_err(),
+        ContractError::WrongSignature {}
+    );
+    do_novate(&mut deps, &s, &novation, &[key, s.keys[1].clone()]).unwrap();
+}
+
+/// Applied novations can not be replayed to hand a participant back to an
+/// identity it was novated away from.
+#[test]
+fn novation_replay() {
+    let (s, mut deps) = funded_init();
+    let (key, identity) = novate_alice(&mut deps, &s);
+    let back = Novation {
+        channel_id: s.cid.clone(),
+        part: 0,
+        old: identity.clone(),
+        new: s.params.participants[0].clone(),
+        nonce: 2u64.into(),
+    };
+    do_novate(&mut deps, &s, &back, &[key, s.keys[1].clone()]).unwrap();
+
+    let replayed = Novation {
+        channel_id: s.cid.clone(),
+        part: 0,
+        old: s.params.participants[0].clone(),
+        new: identity,
+        nonce: 1u64.into(),
+    };
+    assert_eq!(
+        do_novate(&mut deps, &s, &replayed, &s.keys).unwrap_err(),
+        ContractError::NonceUsed {}
+    );
+}
+
+/// The replaced identity must be the current one of the participant.
+#[test]
+fn novation_wrong_old() {
+    let (s, mut deps) = funded_init();
+    let novation = Novation {
+        channel_id: s.cid.clone(),
+        part: 0,
+        old: s.params.participants[1].clone(),
+        new: random_account(&mut rand::thread_rng()).1,
+        nonce: 1u64.into(),
+    };
+    assert_eq!(
+        do_novate(&mut deps, &s, &novation, &s.keys).unwrap_err(),
+        ContractError::WrongParticipant {}
+    );
+}
+
+#[test]
+fn novation_duplicate() {
+    let (s, mut deps) = funded_init();
+    let novation = Novation {
+        channel_id: s.cid.clone(),
+        part: 0,
+        old: s.params.participants[0].clone(),
+        new: s.params.participants[1].clone(),
+        nonce: 1u64.into(),
+    };
+    assert_eq!(
+        do_novate(&mut deps, &s, &novation, &s.keys).unwrap_err(),
+        ContractError::DuplicateParticipant {}
+    );
+}
+
+/// All participants must sign a novation.
+#[test]
+fn novation_missing_sig() {
+    let (s, mut deps) = funded_init();
+    let novation = Novation {
+        channel_id: s.cid.clone(),
+        part: 0,
+        old: s.params.participants[0].clone(),
+        new: random_account(&mut rand::thread_rng()).1,
+        nonce: 1u64.into(),
+    };
+    assert_eq!(
+        do_novate(&mut deps, &s, &novation, &s.keys[..1]).unwrap_err(),
+        ContractError::WrongSignatureNum {}
+    );
+}
+
+/// Rolled over holdings go to the current identity of a participant.
+#[test]
+fn novation_rollover() {
+    let (s, mut deps) = funded_init();
+    let (key, identity) = novate_alice(&mut deps, &s);
+    let keys = [key, s.keys[1].clone()];
+    let sigs = fully_sign(&s.final_state, &keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+
+    let mut params = s.params.clone();
+    params.nonce = random_nonce(&mut rand::thread_rng());
+    let rollover = |params: &Params| {
+        let rollover = Rollover {
+            old_channel: s.cid.clone(),
+            new_channel: params.channel_id().unwrap(),
+        };
+        ExecuteMsg::Rollover {
+            old_channel: s.cid.clone(),
+            new_params: params.clone(),
+            sigs: keys.iter().map(|key| sign(&rollover, key)).collect(),
+        }
+    };
+    // The replaced identity can not take over the holding.
+    assert_eq!(
+        execute_checked(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ALICE, &[]),
+            rollover(&params)
+        )
+        .unwrap_err(),
+        ContractError::WrongParticipant {}
+    );
+
+    params.participants[0] = identity.clone();
+    let msg = rollover(&params);
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap();
+    let cid = params.channel_id().unwrap();
+    let fid = calc_funding_id(&cid, &identity).unwrap();
+    assert_eq!(query_deposit(deps.as_mut(), fid), s.alloc[0]);
+}
@@ -0,0 +1,236 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    contract::instantiate,
+    crypto::OffIdentity,
+    error::ContractError,
+    msg::*,
+    test::common::{crypto::fully_sign, setup::*},
+    types::*,
+};
+use cosmwasm_std::{
+    coin,
+    testing::{mock_dependencies, mock_env, mock_info},
+    Response,
+};
+
+const ADMIN: &str = "admin";
+
+fn do_update_limits(deps: &mut Deps, limits: ChannelLimits) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::SetChannelLimits { limits };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg)
+}
+
+/// Disputes and concludes `state` after re-binding it to `params`.
+fn dispute_and_conclude(
+    deps: &mut Deps,
+    s: &Setup,
+    params: &Params,
+    state: &State,
+) -> Vec<ContractError> {
+    let mut nfinal = state.clone();
+    nfinal.channel_id = params.channel_id().unwrap();
+    nfinal.finalized = false;
+    let mut fin = nfinal.clone();
+    fin.finalized = true;
+
+    let keys = &s.keys[..params.participants.len().min(s.keys.len())];
+    vec![
+        do_dispute(deps.as_mut(), params, &nfinal, &fully_sign(&nfinal, keys)).unwrap_err(),
+        do_conclude(deps.as_mut(), params, &fin, &fully_sign(&fin, keys)).unwrap_err(),
+    ]
+}
+
+#[test]
+fn params_empty_nonce() {
+    let (s, mut deps) = do_init();
+    let mut params = s.params.clone();
+    params.nonce = Default::default();
+
+    for err in dispute_and_conclude(&mut deps, &s, &params, &s.nfinal_state) {
+        assert_eq!(err, ContractError::InvalidNonce {});
+    }
+}
+
+/// Duplicate participants would share a funding id.
+#[test]
+fn params_duplicate_participant() {
+    let (mut s, mut deps) = do_init();
+    let mut params = s.params.clone();
+    params.participants[1] = params.participants[0].clone();
+    s.keys[1] = s.keys[0].clone();
+
+    for err in dispute_and_conclude(&mut deps, &s, &params, &s.nfinal_state) {
+        assert_eq!(err, ContractError::DuplicateParticipant {});
+    }
+}
+
+#[test]
+fn params_malformed_identity() {
+    let bad = [
+        vec![],
+        vec![0x02; 32],
+        vec![0x05; 33],
+        [vec![0x04], vec![1; 32]].concat(),
+    ];
+    for bytes in bad {
+        let mut params = new_setup().params;
+        params.participants[0] = OffIdentity(bytes.as_slice().into());
+        assert_eq!(
+            params.validate().unwrap_err(),
+            ContractError::InvalidIdentity {}
+        );
+    }
+}
+
+#[test]
+fn params_participant_limit() {
+    let limits = ChannelLimits {
+        max_participants: 1,
+        ..Default::default()
+    };
+    let (s, mut deps) = do_init_with(InitMsg {
+        limits,
+        ..Default::default()
+    });
+
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap_err(),
+        ContractError::InvalidParticipantNum {}
+    );
+    let mut params = s.params.clone();
+    params.participants.clear();
+    assert_eq!(
+        params.validate().unwrap_err(),
+        ContractError::InvalidParticipantNum {}
+    );
+}
+
+#[test]
+fn params_dispute_duration_limits() {
+    let limits = ChannelLimits {
+        min_dispute_duration: 10u64.into(),
+        max_dispute_duration: 100u64.into(),
+        ..Default::default()
+    };
+    let (s, mut deps) = do_init_with(InitMsg {
+        limits: limits.clone(),
+        ..Default::default()
+    });
+
+    for duration in [0u64, 9, 101] {
+        let mut params = s.params.clone();
+        params.dispute_duration = duration.into();
+        let mut state = s.nfinal_state.clone();
+        state.channel_id = params.channel_id().unwrap();
+        let sigs = fully_sign(&state, &s.keys);
+        assert_eq!(
+            do_dispute(deps.as_mut(), &params, &state, &sigs).unwrap_err(),
+            ContractError::InvalidDisputeDuration {}
+        );
+    }
+    for duration in [10u64, 100] {
+        let mut params = s.params.clone();
+        params.dispute_duration = duration.into();
+        params.validate_limits(&limits).unwrap();
+    }
+}
+
+#[test]
+fn state_denom_limit() {
+    let limits = ChannelLimits {
+        max_denoms: 2,
+        ..Default::default()
+    };
+    let (s, mut deps) = do_init_with(InitMsg {
+        limits: limits.clone(),
+        ..Default::default()
+    });
+    // The setup uses exactly two denoms.
+    s.nfinal_state.validate_limits(&limits).unwrap();
+
+    let mut state = s.nfinal_state.clone();
+    state.balances[1] = WrappedBalance::from(vec![coin(1, "third")]).0;
+    let sigs = fully_sign(&state, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &state, &sigs).unwrap_err(),
+        ContractError::TooManyDenoms {}
+    );
+}
+
+/// Tightening the limits does not lock the funds of registered channels.
+#[test]
+fn limits_do_not_lock_channels() {
+    let (s, mut deps) = do_init_with(InitMsg {
+        admin: Some(ADMIN.into()),
+        ..Default::default()
+    });
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let limits = ChannelLimits {
+        min_dispute_duration: (s.params.dispute_duration.u64() + 1).into(),
+        max_participants: 1,
+        max_denoms: 1,
+        ..Default::default()
+    };
+    do_update_limits(&mut deps, limits).unwrap();
+
+    // Refuting and concluding still works.
+    let mut state = s.nfinal_state.clone();
+    state.version = (state.version.u64() + 1).into();
+    let sigs = fully_sign(&state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &state, &sigs).unwrap();
+    let env = advance_time(mock_env(), s.params.dispute_duration);
+    do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap();
+}
+
+#[test]
+fn limits_invalid() {
+    let bad = [
+        ChannelLimits {
+            min_dispute_duration: 10u64.into(),
+            max_dispute_duration: 9u64.into(),
+            ..Default::default()
+        },
+        ChannelLimits {
+            max_dispute_duration: (ChannelLimits::MAX_DISPUTE_DURATION + 1).into(),
+            ..Default::default()
+        },
+    ];
+    for limits in bad {
+        let msg = InitMsg {
+            limits: limits.clone(),
+            ..Default::default()
+        };
+        let mut deps = mock_dependencies(&[]);
+        assert_eq!(
+            instantiate(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap_err(),
+            ContractError::InvalidLimits {}
+        );
+
+        let (_, mut deps) = do_init_with(InitMsg {
+            admin: Some(ADMIN.into()),
+            ..Default::default()
+        });
+        assert_eq!(
+            do_update_limits(&mut deps, limits).unwrap_err(),
+            ContractError::InvalidLimits {}
+        );
+    }
+}
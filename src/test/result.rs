@@ -0,0 +1,136 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_env, mock_info},
+    Addr, Response, Uint64,
+};
+use serde::de::DeserializeOwned;
+
+fn data<T: DeserializeOwned>(res: &Response) -> T {
+    from_binary(res.data.as_ref().expect("Response without data")).unwrap()
+}
+
+fn outcome(s: &Setup) -> Vec<Holding> {
+    s.alloc
+        .iter()
+        .enumerate()
+        .map(|(i, bals)| Holding {
+            part: i as PartIdx,
+            amount: bals.clone().into(),
+        })
+        .collect()
+}
+
+#[test]
+fn result_deposit() {
+    let (s, mut deps) = do_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+
+    let res = do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    let result: DepositResult = data(&res);
+    assert_eq!(result.funding_id, s.fids[0]);
+    assert_eq!(
+        result.holding,
+        Vec::from(s.alloc[0].checked_add(&s.alloc[0]).unwrap())
+    );
+}
+
+#[test]
+fn result_dispute_conclude_withdraw() {
+    let (s, mut deps) = funded_init();
+
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    let res = do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+    let env = mock_env();
+    assert_eq!(
+        data::<DisputeResult>(&res),
+        DisputeResult {
+            channel_id: s.cid.clone(),
+            version: s.nfinal_state.version,
+            timeout: env.block.time.plus_seconds(s.params.dispute_duration.u64()),
+        }
+    );
+
+    let env = advance_time(env, s.params.dispute_duration + Uint64::from(1u64));
+    let res = do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap();
+    assert_eq!(
+        data::<ConcludeResult>(&res),
+        ConcludeResult {
+            channel_id: s.cid.clone(),
+            outcome: outcome(&s),
+            fee: vec![],
+        }
+    );
+
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    let res = do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+    assert_eq!(
+        data::<WithdrawResult>(&res),
+        WithdrawResult {
+            funding_id: s.fids[0].clone(),
+            receiver: Addr::unchecked(ALICE),
+            amount: s.alloc[0].clone().into(),
+            fee: vec![],
+        }
+    );
+}
+
+/// Concluding an already concluded channel returns the same result.
+#[test]
+fn result_conclude_twice() {
+    let (s, mut deps) = funded_init();
+
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    let expected = ConcludeResult {
+        channel_id: s.cid.clone(),
+        outcome: outcome(&s),
+        fee: vec![],
+    };
+    for _ in 0..2 {
+        let res = do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+        assert_eq!(data::<ConcludeResult>(&res), expected);
+    }
+}
+
+#[test]
+fn result_admin_config() {
+    let (_, mut deps) = do_init_with(InitMsg {
+        admin: Some(ALICE.into()),
+        ..Default::default()
+    });
+    let msg = ExecuteMsg::SetPaused {
+        deposits: true,
+        disputes: false,
+    };
+    let res = execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap();
+    let ConfigResponse(config) = data(&res);
+    assert!(config.deposits_paused);
+    assert!(!config.disputes_paused);
+}
@@ -0,0 +1,179 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    crypto::Sig,
+    error::ContractError,
+    msg::*,
+    storage::CLOSED,
+    test::common::{
+        crypto::{fully_sign, sign},
+        random::{random_account, random_nonce},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_env, mock_info},
+    Addr, Response,
+};
+
+/// Returns a setup whose channel was funded and concluded.
+fn concluded_init() -> (Setup, Deps) {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+    (s, deps)
+}
+
+/// Returns the params of `s` with a fresh nonce.
+fn next_params(s: &Setup) -> Params {
+    let mut params = s.params.clone();
+    params.nonce = random_nonce(&mut rand::thread_rng());
+    params
+}
+
+fn sign_rollover(s: &Setup, new_params: &Params) -> Vec<Sig> {
+    let rollover = Rollover {
+        old_channel: s.cid.clone(),
+        new_channel: new_params.channel_id().unwrap(),
+    };
+    s.keys.iter().map(|key| sign(&rollover, key)).collect()
+}
+
+fn do_rollover(
+    deps: &mut Deps,
+    s: &Setup,
+    new_params: &Params,
+    sigs: Vec<Sig>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Rollover {
+        old_channel: s.cid.clone(),
+        new_params: new_params.clone(),
+        sigs,
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg)
+}
+
+#[test]
+fn rollover_ok() {
+    let (s, mut deps) = concluded_init();
+    let params = next_params(&s);
+    let sigs = sign_rollover(&s, &params);
+    let res = do_rollover(&mut deps, &s, &params, sigs).unwrap();
+    assert!(res.messages.is_empty());
+
+    let cid = params.channel_id().unwrap();
+    let result: RolloverResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.channel_id, cid);
+    for (i, part) in params.participants.iter().enumerate() {
+        let fid = calc_funding_id(&cid, part).unwrap();
+        assert_eq!(result.deposits[i].funding_id, fid);
+        assert_eq!(query_deposit(deps.as_mut(), fid), s.alloc[i]);
+    }
+    // The old channel is closed like after all participants withdrew.
+    assert!(CLOSED.has(deps.as_ref().storage, s.cid.clone()));
+
+    // The new channel can be concluded with the moved funds.
+    let mut state = s.final_state.clone();
+    state.channel_id = cid;
+    let sigs = fully_sign(&state, &s.keys);
+    do_conclude(deps.as_mut(), &params, &state, &sigs).unwrap();
+}
+
+/// Participants that already withdrew are skipped.
+#[test]
+fn rollover_after_withdraw() {
+    let (s, mut deps) = concluded_init();
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+
+    let params = next_params(&s);
+    let sigs = sign_rollover(&s, &params);
+    let res = do_rollover(&mut deps, &s, &params, sigs).unwrap();
+    let result: RolloverResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.deposits.len(), 1);
+    assert_eq!(result.deposits[0].holding, Vec::from(s.alloc[1].clone()));
+    assert!(CLOSED.has(deps.as_ref().storage, s.cid.clone()));
+}
+
+/// Participants that already withdrew do not need to join the new channel.
+#[test]
+fn rollover_without_withdrawn() {
+    let (s, mut deps) = concluded_init();
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+
+    let mut params = next_params(&s);
+    params.participants[0] = random_account(&mut rand::thread_rng()).1;
+    let sigs = sign_rollover(&s, &params);
+    let res = do_rollover(&mut deps, &s, &params, sigs).unwrap();
+    let result: RolloverResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.deposits.len(), 1);
+    let fid = calc_funding_id(&result.channel_id, &params.participants[1]).unwrap();
+    assert_eq!(result.deposits[0].funding_id, fid);
+}
+
+#[test]
+fn rollover_not_concluded() {
+    let (s, mut deps) = do_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let params = next_params(&s);
+    let sigs = sign_rollover(&s, &params);
+    assert_eq!(
+        do_rollover(&mut deps, &s, &params, sigs).unwrap_err(),
+        ContractError::NotConcluded {}
+    );
+}
+
+#[test]
+fn rollover_wrong_sig() {
+    let (s, mut deps) = concluded_init();
+    let params = next_params(&s);
+    let mut sigs = sign_rollover(&s, &params);
+    sigs.reverse();
+    assert_eq!(
+        do_rollover(&mut deps, &s, &params, sigs).unwrap_err(),
+        ContractError::WrongSignature {}
+    );
+}
+
+/// Every participant of the old channel must take part in the new one.
+#[test]
+fn rollover_missing_participant() {
+    let (s, mut deps) = concluded_init();
+    let mut params = next_params(&s);
+    params.participants[1] = random_account(&mut rand::thread_rng()).1;
+    let sigs = sign_rollover(&s, &params);
+    assert_eq!(
+        do_rollover(&mut deps, &s, &params, sigs).unwrap_err(),
+        ContractError::WrongParticipant {}
+    );
+}
@@ -0,0 +1,178 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    storage::{CLOSED, DISPUTES, HOLDINGS, LIABILITIES, PARAMS},
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    from_binary,
+    testing::{mock_env, mock_info},
+    Addr, BankMsg, CosmosMsg, Order, Response,
+};
+
+const RECEIVERS: [&str; 2] = ["alice-receiver", "bob-receiver"];
+
+fn signed_withdrawals(s: &Setup) -> Vec<SignedWithdrawal> {
+    RECEIVERS
+        .iter()
+        .enumerate()
+        .map(|(i, receiver)| {
+            let withdrawal = Withdrawal {
+                channel_id: s.cid.clone(),
+                part: s.params.participants[i].clone(),
+                receiver: Addr::unchecked(*receiver),
+                fee: vec![],
+            };
+            let sig = sign(&withdrawal, &s.keys[i]);
+            SignedWithdrawal { withdrawal, sig }
+        })
+        .collect()
+}
+
+fn do_settle(
+    deps: &mut Deps,
+    s: &Setup,
+    state: &State,
+    withdrawals: Vec<SignedWithdrawal>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Settle {
+        params: s.params.clone(),
+        state: state.clone(),
+        sigs: fully_sign(state, &s.keys),
+        withdrawals,
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg)
+}
+
+/// Settle pays out all participants and leaves only the tombstone.
+#[test]
+fn settle_ok() {
+    let (s, mut deps) = funded_init();
+    let mut state = s.final_state.clone();
+    state.balances.reverse();
+
+    let res = do_settle(&mut deps, &s, &state, signed_withdrawals(&s)).unwrap();
+    let sends: Vec<_> = res.messages.iter().map(|m| m.msg.clone()).collect();
+    assert_eq!(
+        sends,
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: RECEIVERS[0].into(),
+                amount: s.alloc[1].clone().into(),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: RECEIVERS[1].into(),
+                amount: s.alloc[0].clone().into(),
+            }),
+        ]
+    );
+    let result: SettleResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.channel_id, s.cid);
+    assert_eq!(result.payouts[0].funding_id, s.fids[0]);
+
+    let storage = deps.as_ref().storage;
+    assert_eq!(CLOSED.load(storage, s.cid.clone()).unwrap(), state.version);
+    assert!(!DISPUTES.has(storage, s.cid.clone()));
+    assert!(!PARAMS.has(storage, s.cid.clone()));
+    assert_eq!(
+        HOLDINGS
+            .prefix(s.cid.clone())
+            .range(storage, None, None, Order::Ascending)
+            .count(),
+        0
+    );
+    assert_eq!(
+        LIABILITIES
+            .range(storage, None, None, Order::Ascending)
+            .count(),
+        0
+    );
+    assert_eq!(
+        query_channel_status(deps.as_mut(), mock_env(), &s.params).phase,
+        ChannelPhase::Withdrawn
+    );
+    assert_eq!(
+        do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap_err(),
+        ContractError::ChannelClosed {}
+    );
+}
+
+#[test]
+fn settle_not_final() {
+    let (s, mut deps) = funded_init();
+    assert_eq!(
+        do_settle(&mut deps, &s, &s.nfinal_state, signed_withdrawals(&s)).unwrap_err(),
+        ContractError::StateNotFinal {}
+    );
+}
+
+#[test]
+fn settle_wrong_withdrawals() {
+    let (s, mut deps) = funded_init();
+    let good = signed_withdrawals(&s);
+
+    let mut missing = good.clone();
+    missing.pop();
+    let mut swapped = good.clone();
+    swapped.reverse();
+    let mut wrong_sig = good.clone();
+    wrong_sig[0].sig = good[1].sig.clone();
+    let mut wrong_channel = good.clone();
+    wrong_channel[1].withdrawal.channel_id = Default::default();
+
+    let cases = [
+        (missing, ContractError::WrongWithdrawalNum {}),
+        (swapped, ContractError::WrongParticipant {}),
+        (wrong_sig, ContractError::WrongSignature {}),
+        (wrong_channel, ContractError::WrongChannelId {}),
+    ];
+    for (withdrawals, err) in cases {
+        assert_eq!(
+            do_settle(&mut deps, &s, &s.final_state, withdrawals).unwrap_err(),
+            err
+        );
+    }
+}
+
+/// A running dispute can be settled, a concluded channel not.
+#[test]
+fn settle_disputed() {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let mut old = s.final_state.clone();
+    old.version = (old.version.u64() - 1).into();
+    assert_eq!(
+        do_settle(&mut deps, &s, &old, signed_withdrawals(&s)).unwrap_err(),
+        ContractError::DisputeVersionTooLow {}
+    );
+    do_settle(&mut deps, &s, &s.final_state, signed_withdrawals(&s)).unwrap();
+    assert!(!DISPUTES.has(deps.as_ref().storage, s.cid.clone()));
+
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+    assert_eq!(
+        do_settle(&mut deps, &s, &s.final_state, signed_withdrawals(&s)).unwrap_err(),
+        ContractError::AlreadyConcluded {}
+    );
+}
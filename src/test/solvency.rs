@@ -0,0 +1,111 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    contract::{migrate, query},
+    msg::*,
+    storage::{DEPOSITS, LIABILITIES},
+    test::common::{crypto::fully_sign, setup::*},
+    types::*,
+};
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, MOCK_CONTRACT_ADDR},
+    Coin, Uint128,
+};
+
+fn query_solvency(deps: &Deps) -> SolvencyResponse {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+    from_binary(&res).unwrap()
+}
+
+/// Sets the bank balance of the contract.
+fn set_balance(deps: &mut Deps, balance: &WrappedBalance) {
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, Vec::<Coin>::from(balance.clone()));
+}
+
+#[test]
+fn solvency_after_deposit() {
+    let (s, mut deps) = funded_init();
+    set_balance(&mut deps, &s.outcome);
+
+    let res = query_solvency(&deps);
+    assert!(res.solvent);
+    assert_eq!(
+        res.denoms,
+        vec![
+            DenomSolvency {
+                denom: DENOMS[1].into(),
+                liabilities: Uint128::new(30),
+                balance: Uint128::new(30),
+            },
+            DenomSolvency {
+                denom: DENOMS[0].into(),
+                liabilities: Uint128::new(2),
+                balance: Uint128::new(2),
+            },
+        ]
+    );
+}
+
+/// A missing bank balance is reported.
+#[test]
+fn solvency_insufficient_balance() {
+    let (s, mut deps) = do_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    set_balance(
+        &mut deps,
+        &vec![coin(1, DENOMS[0]), coin(20, DENOMS[1])].into(),
+    );
+
+    let res = query_solvency(&deps);
+    assert!(!res.solvent);
+}
+
+/// Funds that over-fund a channel are no longer owed after conclusion.
+#[test]
+fn solvency_over_funded() {
+    let (s, mut deps) = do_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.outcome, ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+
+    let owed: Vec<Coin> = query_solvency(&deps)
+        .denoms
+        .into_iter()
+        .map(|d| Coin::new(d.liabilities.u128(), d.denom))
+        .collect();
+    assert_eq!(WrappedBalance::from(owed), s.outcome);
+}
+
+/// Migration recomputes the liabilities from all deposits.
+#[test]
+fn solvency_migrate() {
+    let (s, mut deps) = do_init();
+    DEPOSITS
+        .save(deps.as_mut().storage, s.fids[0].clone(), &s.alloc[0])
+        .unwrap();
+    LIABILITIES
+        .save(deps.as_mut().storage, "stale", &Uint128::new(5))
+        .unwrap();
+
+    let msg = MigrateMsg {
+        compact_disputes: false,
+        channels: vec![],
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_accounting(deps.as_ref());
+}
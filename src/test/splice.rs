@@ -0,0 +1,177 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    coin, coins, from_binary,
+    testing::{mock_env, mock_info},
+    Addr, BankMsg, Coin, CosmosMsg, Response,
+};
+
+/// Returns a splice in which Alice adds 5 ATOM and Bob takes out 4 ATOM.
+fn new_splice(s: &Setup) -> Splice {
+    let mut state = s.nfinal_state.clone();
+    state.version = 124u64.into();
+    state.balances[0] = cw0::NativeBalance(vec![coin(25, DENOMS[1]), coin(2, DENOMS[0])]);
+    state.balances[1] = cw0::NativeBalance(coins(6, DENOMS[1]));
+    Splice {
+        state,
+        deposits: vec![SpliceDeposit {
+            part: 0,
+            amount: coins(5, DENOMS[1]),
+        }],
+        payouts: vec![SplicePayout {
+            part: 1,
+            receiver: Addr::unchecked(BOB),
+            amount: coins(4, DENOMS[1]),
+        }],
+    }
+}
+
+fn do_splice(
+    deps: &mut Deps,
+    s: &Setup,
+    splice: &Splice,
+    funds: &[Coin],
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Splice {
+        params: s.params.clone(),
+        splice: splice.clone(),
+        sigs: s.keys.iter().map(|key| sign(splice, key)).collect(),
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(ALICE, funds), msg)
+}
+
+#[test]
+fn splice_in_and_out() {
+    let (s, mut deps) = funded_init();
+    let splice = new_splice(&s);
+    let res = do_splice(&mut deps, &s, &splice, &coins(5, DENOMS[1])).unwrap();
+
+    assert_eq!(
+        res.messages
+            .iter()
+            .map(|m| m.msg.clone())
+            .collect::<Vec<_>>(),
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: BOB.into(),
+            amount: coins(4, DENOMS[1]),
+        })]
+    );
+    let result: SpliceResult = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(result.version, splice.state.version);
+    let alice = s.alloc[0].checked_add(&coins(5, DENOMS[1]).into()).unwrap();
+    let bob = s.alloc[1].checked_sub(&coins(4, DENOMS[1]).into()).unwrap();
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[0].clone()), alice);
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[1].clone()), bob);
+
+    // The splice state can be concluded.
+    let mut state = splice.state.clone();
+    state.finalized = true;
+    let sigs = fully_sign(&state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &state, &sigs).unwrap();
+}
+
+/// States older than the splice can not be used anymore.
+#[test]
+fn splice_checkpoint() {
+    let (s, mut deps) = funded_init();
+    let splice = new_splice(&s);
+    do_splice(&mut deps, &s, &splice, &coins(5, DENOMS[1])).unwrap();
+
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap_err(),
+        ContractError::VersionBelowCheckpoint {}
+    );
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    assert_eq!(
+        do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap_err(),
+        ContractError::VersionBelowCheckpoint {}
+    );
+    // Splicing again needs a newer version.
+    let mut next = splice.clone();
+    next.deposits = vec![];
+    next.payouts = vec![];
+    assert_eq!(
+        do_splice(&mut deps, &s, &next, &[]).unwrap_err(),
+        ContractError::VersionBelowCheckpoint {}
+    );
+    let sigs = fully_sign(&splice.state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &splice.state, &sigs).unwrap();
+}
+
+#[test]
+fn splice_funds_mismatch() {
+    let (s, mut deps) = funded_init();
+    let splice = new_splice(&s);
+    assert_eq!(
+        do_splice(&mut deps, &s, &splice, &coins(4, DENOMS[1])).unwrap_err(),
+        ContractError::SpliceFundsMismatch {}
+    );
+}
+
+/// Payouts can not leave the splice state uncovered.
+#[test]
+fn splice_insufficient_deposits() {
+    let (s, mut deps) = funded_init();
+    let mut splice = new_splice(&s);
+    splice.payouts[0].amount = coins(5, DENOMS[1]);
+    assert_eq!(
+        do_splice(&mut deps, &s, &splice, &coins(5, DENOMS[1])).unwrap_err(),
+        ContractError::InsufficientDeposits {}
+    );
+    splice.payouts[0].amount = coins(11, DENOMS[1]);
+    assert_eq!(
+        do_splice(&mut deps, &s, &splice, &coins(5, DENOMS[1])).unwrap_err(),
+        ContractError::InsufficientDeposits {}
+    );
+}
+
+#[test]
+fn splice_disputed() {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let splice = new_splice(&s);
+    assert_eq!(
+        do_splice(&mut deps, &s, &splice, &coins(5, DENOMS[1])).unwrap_err(),
+        ContractError::ChannelDisputed {}
+    );
+}
+
+#[test]
+fn splice_wrong_sig() {
+    let (s, mut deps) = funded_init();
+    let splice = new_splice(&s);
+    let msg = ExecuteMsg::Splice {
+        params: s.params.clone(),
+        splice: splice.clone(),
+        sigs: fully_sign(&splice.state, &s.keys),
+    };
+    let info = mock_info(ALICE, &coins(5, DENOMS[1]));
+    assert_eq!(
+        execute_checked(deps.as_mut(), mock_env(), info, msg).unwrap_err(),
+        ContractError::WrongSignature {}
+    );
+}
@@ -0,0 +1,109 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    msg::*,
+    test::common::{
+        crypto::{fully_sign, sign},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{testing::mock_env, Uint64};
+
+/// The status follows the channel through all phases.
+#[test]
+fn channel_status_phases() {
+    let (s, mut deps) = do_init();
+
+    // OPEN
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    let status = query_channel_status(deps.as_mut(), mock_env(), &s.params);
+    assert_eq!(
+        status,
+        ChannelStatusResponse {
+            phase: ChannelPhase::Open,
+            version: None,
+            timeout_in: None,
+            parts: vec![
+                PartStatus {
+                    holding: s.alloc[0].clone().into(),
+                    withdrawn: false,
+                },
+                PartStatus {
+                    holding: vec![],
+                    withdrawn: false,
+                },
+            ],
+        }
+    );
+
+    // DISPUTED
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+    let env = advance_time(mock_env(), Uint64::from(10u64));
+    let status = query_channel_status(deps.as_mut(), env, &s.params);
+    assert_eq!(status.phase, ChannelPhase::Disputed);
+    assert_eq!(status.version, Some(s.nfinal_state.version));
+    assert_eq!(
+        status.timeout_in,
+        Some((s.params.dispute_duration.u64() - 10).into())
+    );
+    // The remaining time does not underflow after the timeout.
+    let env = advance_time(mock_env(), s.params.dispute_duration + Uint64::from(1u64));
+    let status = query_channel_status(deps.as_mut(), env.clone(), &s.params);
+    assert_eq!(status.timeout_in, Some(Uint64::zero()));
+
+    // CONCLUDED
+    do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap();
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: cosmwasm_std::Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+    let status = query_channel_status(deps.as_mut(), mock_env(), &s.params);
+    assert_eq!(status.phase, ChannelPhase::Concluded);
+    assert_eq!(status.timeout_in, None);
+    assert_eq!(
+        status.parts,
+        vec![
+            PartStatus {
+                holding: vec![],
+                withdrawn: true,
+            },
+            PartStatus {
+                holding: s.alloc[1].clone().into(),
+                withdrawn: false,
+            },
+        ]
+    );
+
+    // WITHDRAWN
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[1].clone(),
+        receiver: cosmwasm_std::Addr::unchecked(BOB),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[1]);
+    do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+    let status = query_channel_status(deps.as_mut(), mock_env(), &s.params);
+    assert_eq!(status.phase, ChannelPhase::Withdrawn);
+    assert_eq!(status.version, Some(s.nfinal_state.version));
+    assert!(status.parts.iter().all(|p| p.withdrawn));
+}
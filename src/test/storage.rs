@@ -0,0 +1,315 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    contract::{instantiate, migrate, query},
+    error::ContractError,
+    msg::*,
+    storage::{LegacyDispute, DEPOSITS, DISPUTES, FUNDING_IDS, HOLDINGS, LEGACY_DISPUTES},
+    test::common::{
+        crypto::{fully_sign, sign},
+        random::{random_account, random_nonce},
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_dependencies, mock_env, mock_info},
+    Coin, Storage, Timestamp, Uint64,
+};
+
+fn compact_init() -> (Setup, Deps) {
+    do_init_with(InitMsg {
+        compact_disputes: true,
+        ..Default::default()
+    })
+}
+
+/// Compact disputes only store the digest of the state.
+#[test]
+fn compact_dispute_stores_digest() {
+    let (s, mut deps) = compact_init();
+
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Dispute(s.cid.clone())).unwrap();
+    let DisputeResponse(dispute) = from_binary(&res).unwrap();
+    assert_eq!(dispute.state.state, None);
+    assert_eq!(dispute.state.version, s.nfinal_state.version);
+    assert_eq!(dispute.state.hash, s.nfinal_state.hash().unwrap());
+}
+
+/// Concluding a compact dispute requires the registered state as preimage.
+#[test]
+fn compact_conclude_dispute_preimage() {
+    let (s, mut deps) = compact_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+
+    let mut state = s.nfinal_state.clone();
+    state.balances.reverse();
+    let sigs = fully_sign(&state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &state, &sigs).unwrap();
+
+    let env = advance_time(mock_env(), s.params.dispute_duration + Uint64::from(1u64));
+    assert_eq!(
+        do_conclude_dispute(deps.as_mut(), env.clone(), &s.cid, None).unwrap_err(),
+        ContractError::MissingPreimage {}
+    );
+    assert_eq!(
+        do_conclude_dispute(deps.as_mut(), env.clone(), &s.cid, Some(&s.nfinal_state)).unwrap_err(),
+        ContractError::WrongPreimage {}
+    );
+    do_conclude_dispute(deps.as_mut(), env, &s.cid, Some(&state)).unwrap();
+
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[0].clone()), s.alloc[1]);
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[1].clone()), s.alloc[0]);
+}
+
+/// A compactly concluded channel can only be concluded again with the same
+/// state.
+#[test]
+fn compact_conclude_twice() {
+    let (s, mut deps) = compact_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+
+    let mut state = s.final_state.clone();
+    state.balances.reverse();
+    let sigs = fully_sign(&state, &s.keys);
+    assert_eq!(
+        do_conclude(deps.as_mut(), &s.params, &state, &sigs).unwrap_err(),
+        ContractError::ConcludedWithDifferentState {}
+    );
+}
+
+/// Migration converts legacy disputes into the current layout.
+#[test]
+fn migrate_legacy_disputes() {
+    for compact in [false, true] {
+        let (s, mut deps) = do_init();
+        let legacy = LegacyDispute {
+            state: s.nfinal_state.clone(),
+            timeout: Timestamp::from_seconds(100),
+            concluded: false,
+        };
+        LEGACY_DISPUTES
+            .save(deps.as_mut().storage, s.cid.clone(), &legacy)
+            .unwrap();
+        assert!(DISPUTES.load(deps.as_ref().storage, s.cid.clone()).is_err());
+
+        let msg = MigrateMsg {
+            compact_disputes: compact,
+            channels: vec![],
+        };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let dispute = DISPUTES.load(deps.as_ref().storage, s.cid.clone()).unwrap();
+        assert_eq!(
+            dispute,
+            Dispute {
+                state: RegisteredState::new(&s.nfinal_state, compact).unwrap(),
+                timeout: legacy.timeout,
+                concluded: legacy.concluded,
+            }
+        );
+    }
+}
+
+/// Migrating to compact disputes drops the states of current disputes.
+#[test]
+fn migrate_to_compact() {
+    let (s, mut deps) = do_init();
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+
+    let msg = MigrateMsg {
+        compact_disputes: true,
+        channels: vec![],
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let dispute = DISPUTES.load(deps.as_ref().storage, s.cid.clone()).unwrap();
+    assert_eq!(
+        dispute.state,
+        RegisteredState::new(&s.nfinal_state, true).unwrap()
+    );
+}
+
+fn query_holdings(deps: &Deps, cid: &ChannelId) -> Result<Vec<Holding>, ContractError> {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Holdings(cid.clone()))?;
+    let HoldingsResponse(holdings) = from_binary(&res).unwrap();
+    Ok(holdings)
+}
+
+/// Deposits are grouped by channel once the channel is registered.
+#[test]
+fn holdings_after_register() {
+    let (s, mut deps) = do_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    assert_eq!(
+        query_holdings(&deps, &s.cid).unwrap_err(),
+        ContractError::UnknownChannel {}
+    );
+
+    // Disputing registers the channel.
+    let sigs = fully_sign(&s.nfinal_state, &s.keys);
+    do_dispute(deps.as_mut(), &s.params, &s.nfinal_state, &sigs).unwrap();
+    assert!(DEPOSITS
+        .may_load(deps.as_ref().storage, s.fids[0].clone())
+        .unwrap()
+        .is_none());
+    // Later deposits go to the channel directly.
+    do_deposit(deps.as_mut(), &s.fids[1], &s.alloc[1], BOB.into()).unwrap();
+
+    let holdings = query_holdings(&deps, &s.cid).unwrap();
+    assert_eq!(
+        holdings,
+        vec![
+            Holding {
+                part: 0,
+                amount: s.alloc[0].clone().into(),
+            },
+            Holding {
+                part: 1,
+                amount: s.alloc[1].clone().into(),
+            },
+        ]
+    );
+    // Funding ids still resolve.
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[0].clone()), s.alloc[0]);
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[1].clone()), s.alloc[1]);
+}
+
+/// Migration moves the deposits of the given channels into the per-channel
+/// layout.
+#[test]
+fn migrate_register_channels() {
+    let (s, mut deps) = funded_init();
+
+    let msg = MigrateMsg {
+        compact_disputes: false,
+        channels: vec![s.params.clone()],
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+    for (i, fid) in s.fids.iter().enumerate() {
+        let storage = deps.as_ref().storage;
+        assert!(DEPOSITS.may_load(storage, fid.clone()).unwrap().is_none());
+        assert_eq!(
+            FUNDING_IDS.load(storage, fid.clone()).unwrap(),
+            (s.cid.clone(), i as PartIdx)
+        );
+        let key = (s.cid.clone(), (i as PartIdx).into());
+        assert_eq!(HOLDINGS.load(storage, key).unwrap(), s.alloc[i]);
+    }
+}
+
+/// Outcomes that were pushed into the old layout can still be withdrawn.
+#[test]
+fn withdraw_legacy_outcome() {
+    let (s, mut deps) = do_init();
+    DEPOSITS
+        .save(deps.as_mut().storage, s.fids[0].clone(), &s.alloc[0])
+        .unwrap();
+    let legacy = LegacyDispute {
+        state: s.final_state.clone(),
+        timeout: Timestamp::from_seconds(0),
+        concluded: true,
+    };
+    LEGACY_DISPUTES
+        .save(deps.as_mut().storage, s.cid.clone(), &legacy)
+        .unwrap();
+    let msg = MigrateMsg {
+        compact_disputes: false,
+        channels: vec![],
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: cosmwasm_std::Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+    assert_eq!(
+        query_deposit(deps.as_mut(), s.fids[0].clone()),
+        Default::default()
+    );
+}
+
+/// Stored bytes of a dispute in the full and compact layout per number of
+/// participants and denoms, as listed in the README.
+const DISPUTE_SIZES: [(usize, usize, usize, usize); 4] = [
+    (2, 1, 394, 184),
+    (2, 8, 1122, 184),
+    (8, 8, 3630, 184),
+    (16, 32, 27294, 184),
+];
+
+/// Compares the stored size of a dispute in the full and compact layout.
+#[test]
+fn dispute_storage_size() {
+    let mut rng = rand::thread_rng();
+    for (num_parts, num_denoms, full, compact) in DISPUTE_SIZES {
+        let keys: Vec<_> = (0..num_parts).map(|_| random_account(&mut rng)).collect();
+        let params = Params {
+            nonce: random_nonce(&mut rng),
+            participants: keys.iter().map(|k| k.1.clone()).collect(),
+            dispute_duration: 60u64.into(),
+            bond: vec![],
+            arbiter: None,
+            expires_at: None,
+        };
+        let balance = (0..num_denoms)
+            .map(|i| coin(u64::MAX.into(), format!("denom-{}", i)))
+            .collect::<Vec<Coin>>();
+        let state = State {
+            channel_id: params.channel_id().unwrap(),
+            version: 1u64.into(),
+            balances: vec![WrappedBalance::from(balance).0; num_parts],
+            finalized: false,
+            fee: vec![],
+            locks: vec![],
+        };
+        let sks: Vec<_> = keys.into_iter().map(|k| k.0).collect();
+        let sigs = fully_sign(&state, &sks);
+
+        let sizes: Vec<usize> = [false, true]
+            .iter()
+            .map(|compact| {
+                let mut deps = mock_dependencies(&[]);
+                let msg = InitMsg {
+                    compact_disputes: *compact,
+                    ..Default::default()
+                };
+                instantiate(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap();
+                do_dispute(deps.as_mut(), &params, &state, &sigs).unwrap();
+
+                let key = DISPUTES.key(state.channel_id.clone());
+                let value = deps.storage.get(&key).unwrap();
+                key.len() + value.len()
+            })
+            .collect();
+        assert_eq!(sizes, [full, compact]);
+    }
+}
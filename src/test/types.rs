@@ -13,6 +13,7 @@
 //  limitations under the License.
 
 use crate::{
+    error::ContractError,
     test::common::{
         crypto::{fully_sign, sign},
         random::{random_account, random_params_state, random_state, random_withdrawal},
@@ -57,10 +58,45 @@ fn native_balance_normalize() {
 }
 
 #[test]
-#[should_panic(expected = "attempt to add with overflow")]
 fn native_balance_overflow() {
     let a = WrappedBalance::from(coins(u128::MAX - 1, "PRN"));
-    let _ = a.clone() + &a;
+    assert_eq!(
+        a.checked_add(&a).unwrap_err(),
+        ContractError::BalanceOverflow {
+            denom: "PRN".into()
+        }
+    );
+}
+
+#[test]
+fn native_balance_checked_add_sub() {
+    let a = WrappedBalance::from(vec![coin(2u128, "PRN"), coin(3u128, "ETH")]);
+    let b = WrappedBalance::from(vec![coin(1u128, "PRN"), coin(4u128, "BTC")]);
+
+    let sum = a.checked_add(&b).unwrap();
+    assert_eq!(
+        sum,
+        WrappedBalance::from(vec![
+            coin(4u128, "BTC"),
+            coin(3u128, "ETH"),
+            coin(3u128, "PRN")
+        ])
+    );
+    assert_eq!(sum.checked_sub(&b).unwrap(), a);
+    // Denoms that drop to zero are removed.
+    assert_eq!(sum.checked_sub(&sum).unwrap(), WrappedBalance::default());
+    assert_eq!(
+        a.checked_sub(&b).unwrap_err(),
+        ContractError::BalanceUnderflow {
+            denom: "BTC".into()
+        }
+    );
+    assert_eq!(
+        b.checked_sub(&a).unwrap_err(),
+        ContractError::BalanceUnderflow {
+            denom: "ETH".into()
+        }
+    );
 }
 
 #[test]
@@ -81,7 +117,9 @@ fn state_sig_verify_full() {
 
     let sigs = fully_sign(&state, &sks);
     let deps = mock_dependencies(&[]);
-    assert!(state.verify_fully_signed(&params, &sigs, &deps.api).is_ok());
+    assert!(state
+        .verify_fully_signed(&params, &params.participants, &sigs, &deps.api)
+        .is_ok());
 }
 
 #[test]
@@ -91,5 +129,5 @@ fn withdrawal_sig_verify() {
 
     let sig = sign(&withdrawal, &sks[index]);
     let deps = mock_dependencies(&[]);
-    assert!(withdrawal.verify(&sig, &deps.api).is_ok());
+    assert!(withdrawal.verify(&sig, &withdrawal.part, &deps.api).is_ok());
 }
@@ -0,0 +1,172 @@
+//  Copyright 2021 PolyCrypt GmbH
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use crate::{
+    error::ContractError,
+    msg::*,
+    storage::REWARDS,
+    test::common::{
+        crypto::{fully_sign, sign},
+        random::random_account,
+        setup::*,
+    },
+    types::*,
+};
+use cosmwasm_std::{
+    coin,
+    testing::{mock_env, mock_info},
+    Addr, BankMsg, CosmosMsg, Response, Uint64,
+};
+
+const WATCHER: &str = "watcher";
+
+/// Authorization of [WATCHER] by Alice.
+fn alice_auth(s: &Setup, reward: &WrappedBalance) -> SignedWatcherAuth {
+    let auth = WatcherAuth {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        watcher: Addr::unchecked(WATCHER),
+        reward: reward.clone().into(),
+        expiry: mock_env().block.time.plus_seconds(1000),
+    };
+    let sig = sign(&auth, &s.keys[0]);
+    SignedWatcherAuth { auth, sig }
+}
+
+fn watch(
+    deps: &mut Deps,
+    s: &Setup,
+    state: &State,
+    sender: &str,
+    watcher: SignedWatcherAuth,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Dispute {
+        params: s.params.clone(),
+        state: state.clone(),
+        sigs: fully_sign(state, &s.keys),
+        arbiter_sig: None,
+        watcher: Some(watcher),
+    };
+    execute_checked(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+}
+
+/// Returns a state that refutes the dispute of [disputed_init].
+fn newer_state(s: &Setup) -> State {
+    let mut newer = s.nfinal_state.clone();
+    newer.version = (newer.version.u64() + 1).into();
+    newer
+}
+
+fn conclude_after_timeout(deps: &mut Deps, s: &Setup) -> Response {
+    let env = advance_time(mock_env(), s.params.dispute_duration + Uint64::from(1u64));
+    do_conclude_dispute(deps.as_mut(), env, &s.cid, None).unwrap()
+}
+
+/// The reward is paid out of Alice's outcome at conclusion.
+#[test]
+fn watcher_reward() {
+    let (s, mut deps) = disputed_init();
+    let newer = newer_state(&s);
+    let reward = WrappedBalance::from(vec![coin(5, DENOMS[1])]);
+    watch(&mut deps, &s, &newer, WATCHER, alice_auth(&s, &reward)).unwrap();
+
+    let res = conclude_after_timeout(&mut deps, &s);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: WATCHER.into(),
+            amount: reward.clone().into(),
+        })
+    );
+    assert_eq!(
+        query_deposit(deps.as_mut(), s.fids[0].clone()),
+        s.alloc[0].checked_sub(&reward).unwrap()
+    );
+    assert_eq!(query_deposit(deps.as_mut(), s.fids[1].clone()), s.alloc[1]);
+    assert!(!REWARDS.has(deps.as_ref().storage, (s.cid.clone(), 0u16.into())));
+}
+
+/// Rewards are capped by the outcome of the participant.
+#[test]
+fn watcher_reward_capped() {
+    let (s, mut deps) = disputed_init();
+    let newer = newer_state(&s);
+    let reward = WrappedBalance::from(vec![coin(100, DENOMS[1]), coin(1, "other")]);
+    watch(&mut deps, &s, &newer, WATCHER, alice_auth(&s, &reward)).unwrap();
+
+    let res = conclude_after_timeout(&mut deps, &s);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: WATCHER.into(),
+            amount: vec![coin(20, DENOMS[1])],
+        })
+    );
+    assert_eq!(
+        query_deposit(deps.as_mut(), s.fids[0].clone()),
+        vec![coin(2, DENOMS[0])].into()
+    );
+}
+
+/// Starting a dispute is not a refutation and earns nothing.
+#[test]
+fn watcher_first_dispute() {
+    let (s, mut deps) = do_init();
+    let reward = WrappedBalance::from(vec![coin(5, DENOMS[1])]);
+    watch(
+        &mut deps,
+        &s,
+        &s.nfinal_state,
+        WATCHER,
+        alice_auth(&s, &reward),
+    )
+    .unwrap();
+    assert!(!REWARDS.has(deps.as_ref().storage, (s.cid.clone(), 0u16.into())));
+}
+
+#[test]
+fn watcher_invalid_auth() {
+    let (s, mut deps) = disputed_init();
+    let newer = newer_state(&s);
+    let reward = WrappedBalance::from(vec![coin(5, DENOMS[1])]);
+
+    assert_eq!(
+        watch(&mut deps, &s, &newer, BOB, alice_auth(&s, &reward)).unwrap_err(),
+        ContractError::InvalidWatcher {}
+    );
+
+    let mut expired = alice_auth(&s, &reward);
+    expired.auth.expiry = mock_env().block.time;
+    expired.sig = sign(&expired.auth, &s.keys[0]);
+    assert_eq!(
+        watch(&mut deps, &s, &newer, WATCHER, expired).unwrap_err(),
+        ContractError::WatcherAuthExpired {}
+    );
+
+    let mut stranger = alice_auth(&s, &reward);
+    let (sk, pk) = random_account(&mut rand::thread_rng());
+    stranger.auth.part = pk;
+    stranger.sig = sign(&stranger.auth, &sk);
+    assert_eq!(
+        watch(&mut deps, &s, &newer, WATCHER, stranger).unwrap_err(),
+        ContractError::WrongParticipant {}
+    );
+
+    let mut wrong_sig = alice_auth(&s, &reward);
+    wrong_sig.sig = sign(&wrong_sig.auth, &s.keys[1]);
+    assert_eq!(
+        watch(&mut deps, &s, &newer, WATCHER, wrong_sig).unwrap_err(),
+        ContractError::WrongSignature {}
+    );
+}
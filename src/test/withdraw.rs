@@ -14,6 +14,7 @@
 
 use crate::{
     error::ContractError,
+    storage::{CLOSED, DISPUTES, HOLDINGS, PARAMS, WITHDRAWN},
     test::common::{
         crypto::{fully_sign, sign},
         random::*,
@@ -29,6 +30,7 @@ fn withdraw_wrong_sig() {
         channel_id: s.cid,
         part: s.params.participants[0].clone(), // Alice wants to withdraw
         receiver: cosmwasm_std::Addr::unchecked(ALICE),
+        fee: vec![],
     };
     let sig = sign(&withdrawal, &s.keys[1]); // But Bob signed
 
@@ -45,6 +47,7 @@ fn withdraw_unknown_channel() {
         channel_id: s.cid,
         part: s.params.participants[0].clone(),
         receiver: cosmwasm_std::Addr::unchecked(ALICE),
+        fee: vec![],
     };
     let sig = sign(&withdrawal, &s.keys[0]);
 
@@ -65,6 +68,7 @@ fn withdraw_not_concluded() {
         channel_id: s.cid,
         part: s.params.participants[0].clone(),
         receiver: cosmwasm_std::Addr::unchecked(ALICE),
+        fee: vec![],
     };
     let sig = sign(&withdrawal, &s.keys[0]);
 
@@ -90,6 +94,7 @@ fn withdraw_unknown_deposit() {
         channel_id: s.cid,
         part: carl.1, // Carl wants to withdraw
         receiver: cosmwasm_std::Addr::unchecked(ALICE),
+        fee: vec![],
     };
     let sig = sign(&withdrawal, &carl.0); // Carl signed
 
@@ -126,6 +131,7 @@ fn withdraw_ok() {
             channel_id: s.cid.clone(),
             part: s.params.participants[0].clone(),
             receiver: cosmwasm_std::Addr::unchecked(ALICE),
+            fee: vec![],
         };
         let sig = sign(&withdrawal, &s.keys[0]);
 
@@ -142,14 +148,15 @@ fn withdraw_ok() {
             channel_id: s.cid,
             part: s.params.participants[1].clone(),
             receiver: cosmwasm_std::Addr::unchecked(BOB),
+            fee: vec![],
         };
         let sig = sign(&withdrawal, &s.keys[1]);
 
         do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
-        // Withdrawing twice errors.
+        // Withdrawing twice errors since the channel is closed now.
         assert_eq!(
             do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap_err(),
-            ContractError::UnknownDeposit {}
+            ContractError::ChannelClosed {}
         );
     }
 
@@ -159,3 +166,91 @@ fn withdraw_ok() {
     let deposited = query_deposit(deps.as_mut(), s.fids[1].clone());
     assert_eq!(deposited, Default::default());
 }
+
+fn withdraw_all(s: &Setup, deps: &mut Deps) {
+    for (i, part) in s.params.participants.iter().enumerate() {
+        let withdrawal = Withdrawal {
+            channel_id: s.cid.clone(),
+            part: part.clone(),
+            receiver: cosmwasm_std::Addr::unchecked(ALICE),
+            fee: vec![],
+        };
+        let sig = sign(&withdrawal, &s.keys[i]);
+        do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+    }
+}
+
+/// The withdrawal status is tracked per participant until all withdrew.
+#[test]
+fn withdraw_tracks_parts() {
+    let (s, mut deps) = do_init();
+    do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap();
+    let mut state = s.final_state.clone();
+    state.balances = vec![s.alloc[0].0.clone(), Default::default()];
+    let sigs = fully_sign(&state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &state, &sigs).unwrap();
+
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[0].clone(),
+        receiver: cosmwasm_std::Addr::unchecked(ALICE),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[0]);
+    let res = do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    let storage = deps.as_ref().storage;
+    assert!(WITHDRAWN.has(storage, (s.cid.clone(), 0u16.into())));
+    assert!(!WITHDRAWN.has(storage, (s.cid.clone(), 1u16.into())));
+    assert!(DISPUTES.has(storage, s.cid.clone()));
+
+    // Bob withdraws nothing, which sends no funds.
+    let withdrawal = Withdrawal {
+        channel_id: s.cid.clone(),
+        part: s.params.participants[1].clone(),
+        receiver: cosmwasm_std::Addr::unchecked(BOB),
+        fee: vec![],
+    };
+    let sig = sign(&withdrawal, &s.keys[1]);
+    let res = do_withdraw(deps.as_mut(), &withdrawal, &sig).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+/// A fully withdrawn channel is removed and leaves a tombstone.
+#[test]
+fn withdraw_closes_channel() {
+    let (s, mut deps) = funded_init();
+    let sigs = fully_sign(&s.final_state, &s.keys);
+    do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap();
+    withdraw_all(&s, &mut deps);
+
+    let storage = deps.as_ref().storage;
+    assert_eq!(
+        CLOSED.load(storage, s.cid.clone()).unwrap(),
+        s.final_state.version
+    );
+    assert!(!DISPUTES.has(storage, s.cid.clone()));
+    assert!(!PARAMS.has(storage, s.cid.clone()));
+    for i in 0..2u16 {
+        assert!(!HOLDINGS.has(storage, (s.cid.clone(), i.into())));
+        assert!(!WITHDRAWN.has(storage, (s.cid.clone(), i.into())));
+    }
+
+    // The channel can not be used again.
+    assert_eq!(
+        do_conclude(deps.as_mut(), &s.params, &s.final_state, &sigs).unwrap_err(),
+        ContractError::ChannelClosed {}
+    );
+    let mut state = s.nfinal_state.clone();
+    state.version = (state.version.u64() + 1).into();
+    let sigs = fully_sign(&state, &s.keys);
+    assert_eq!(
+        do_dispute(deps.as_mut(), &s.params, &state, &sigs).unwrap_err(),
+        ContractError::ChannelClosed {}
+    );
+    assert_eq!(
+        do_deposit(deps.as_mut(), &s.fids[0], &s.alloc[0], ALICE.into()).unwrap_err(),
+        ContractError::ChannelClosed {}
+    );
+}
@@ -18,13 +18,12 @@ use crate::{
     ensure,
     error::ContractError,
 };
-use cosmwasm_std::{Api, Binary, Coin, Timestamp, Uint64};
+use cosmwasm_std::{Api, Binary, Coin, Timestamp, Uint128, Uint64};
 use cw0::NativeBalance;
-use cw_storage_plus::PrimaryKey;
+use cw_storage_plus::{Prefixer, PrimaryKey};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
-use std::ops::Add;
 
 /// WrappedBinary is a wrapper around Binary that enables usage as a map key.
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
@@ -45,6 +44,12 @@ impl<'a> PrimaryKey<'a> for WrappedBinary {
     }
 }
 
+impl<'a> Prefixer<'a> for WrappedBinary {
+    fn prefix(&self) -> Vec<&[u8]> {
+        vec![self.0.as_slice()]
+    }
+}
+
 impl std::convert::From<&[u8]> for WrappedBinary {
     fn from(val: &[u8]) -> Self {
         WrappedBinary(val.into())
@@ -75,6 +80,9 @@ pub struct Funding {
     pub part: OffIdentity,
 }
 
+/// Index of a participant in [Params::participants].
+pub type PartIdx = u16;
+
 /// Random value that is used to make the [Params] of a channel unique.
 pub type Nonce = Binary;
 /// Timely duration in seconds.
@@ -100,6 +108,43 @@ pub struct Params {
     ///
     /// Describes how long a dispute will be held open.
     pub dispute_duration: Seconds,
+
+    /// Bond that must be attached when opening a dispute.
+    ///
+    /// Overrides [Config::dispute_bond] if not empty.
+    /// Omitted from the encoding when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bond: Vec<Coin>,
+
+    /// Third party that can resolve disputes, see [Arbiter].
+    ///
+    /// Omitted from the encoding when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arbiter: Option<Arbiter>,
+
+    /// End of the lifetime of the channel.
+    ///
+    /// Afterwards anyone can conclude the channel with
+    /// [crate::msg::ExecuteMsg::ConcludeExpired], while no new disputes or
+    /// checkpoints are accepted.
+    /// Omitted from the encoding when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Arbiter of a channel.
+///
+/// A state signed by the arbiter is accepted by
+/// [crate::msg::ExecuteMsg::Dispute] and [crate::msg::ExecuteMsg::Conclude]
+/// in place of the participant signatures once a dispute has been open for
+/// `delay`.
+pub struct Arbiter {
+    /// Off-chain identity that signs the resolutions.
+    pub identity: OffIdentity,
+    /// Time that a dispute must be open before it can be arbitrated.
+    /// At most [ChannelLimits::max_dispute_duration].
+    pub delay: Seconds,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -131,6 +176,53 @@ pub struct State {
     /// An honest participant will never sign another state after he signed a
     /// final state.
     pub finalized: bool,
+
+    /// Relayer fee that is paid to the account which concludes this state.
+    ///
+    /// Comes out of the deposits of the channel in addition to the
+    /// [State::balances]. Omitted from the encoding when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fee: Vec<Coin>,
+
+    /// Conditional payments between participants, see [Lock].
+    ///
+    /// Locked amounts are not part of the [State::balances] and are
+    /// resolved when the channel is concluded. Omitted from the encoding
+    /// when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locks: Vec<Lock>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Hash time-locked payment from one participant to another.
+///
+/// Goes to the receiver if the preimage of `hash` was revealed with
+/// [crate::msg::ExecuteMsg::RevealPreimage] before `timeout`, and back to
+/// the sender otherwise.
+pub struct Lock {
+    /// SHA-256 hash of the preimage.
+    pub hash: Hash,
+    pub timeout: Timestamp,
+    pub amount: Vec<Coin>,
+    /// Index of the paying participant in [Params::participants].
+    pub sender: PartIdx,
+    /// Index of the receiving participant in [Params::participants].
+    pub receiver: PartIdx,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// On-chain record of a [State].
+///
+/// Always holds the version and hash of the state. The state itself is
+/// omitted when [Config::compact_disputes] is set, callers then have to
+/// supply it as preimage of the hash.
+pub struct RegisteredState {
+    /// Version of the state.
+    pub version: Version,
+    /// Hash of the state, see [State::hash].
+    pub hash: Hash,
+    /// The full state, unless stored compactly.
+    pub state: Option<State>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -139,13 +231,93 @@ pub struct State {
 /// timeout did not run out.
 pub struct Dispute {
     /// The state of the disputed channel.
-    pub state: State,
+    pub state: RegisteredState,
     /// Timeout of the dispute.
     pub timeout: Timestamp,
     /// Indicates whether the dispute has been concluded.
     pub concluded: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
+/// Contract-wide configuration.
+pub struct Config {
+    /// Store only the digest of disputed states instead of the full state.
+    ///
+    /// Saves storage gas for channels with many participants or assets.
+    pub compact_disputes: bool,
+    /// Account that can pause the contract, see [crate::msg::ExecuteMsg::SetPaused].
+    #[serde(default)]
+    pub admin: Option<OnIdentity>,
+    /// Whether deposits are paused.
+    #[serde(default)]
+    pub deposits_paused: bool,
+    /// Whether disputes are paused.
+    #[serde(default)]
+    pub disputes_paused: bool,
+    /// Denoms that can be deposited, all denoms are allowed if not set.
+    #[serde(default)]
+    pub allowed_denoms: Option<Vec<String>>,
+    /// Maximal holding of a single funding id per denom.
+    ///
+    /// Denoms without a cap are unlimited.
+    #[serde(default)]
+    pub funding_caps: Vec<Coin>,
+    /// Maximal total amount that the contract holds per denom.
+    ///
+    /// Denoms without a cap are unlimited.
+    #[serde(default)]
+    pub denom_caps: Vec<Coin>,
+    /// Limits that the [Params] and [State] of every channel must respect.
+    #[serde(default)]
+    pub limits: ChannelLimits,
+    /// Bond that must be attached when opening a dispute, see [Params::bond].
+    #[serde(default)]
+    pub dispute_bond: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Structural limits of channels, see [Params::validate_limits].
+///
+/// Only enforced when a channel is registered, so that changing them
+/// never locks the funds of existing channels.
+pub struct ChannelLimits {
+    /// Minimal [Params::dispute_duration].
+    pub min_dispute_duration: Seconds,
+    /// Maximal [Params::dispute_duration].
+    pub max_dispute_duration: Seconds,
+    /// Maximal number of [Params::participants].
+    pub max_participants: PartIdx,
+    /// Maximal number of distinct denoms in the balances of a [State].
+    pub max_denoms: u32,
+}
+
+impl Default for ChannelLimits {
+    fn default() -> Self {
+        Self {
+            min_dispute_duration: 1u64.into(),
+            max_dispute_duration: (365 * 24 * 60 * 60u64).into(),
+            max_participants: 64,
+            max_denoms: 32,
+        }
+    }
+}
+
+impl ChannelLimits {
+    /// Upper bound of [ChannelLimits::max_dispute_duration].
+    /// Keeps dispute timeouts far away from overflowing a [Timestamp].
+    pub const MAX_DISPUTE_DURATION: u64 = 10 * 365 * 24 * 60 * 60;
+
+    /// Checks that these limits are consistent.
+    pub fn validate(&self) -> Result<(), ContractError> {
+        ensure!(
+            self.min_dispute_duration <= self.max_dispute_duration
+                && self.max_dispute_duration.u64() <= Self::MAX_DISPUTE_DURATION,
+            ContractError::InvalidLimits {}
+        );
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 /// Withdrawal authorization for on-chain funds.
 ///
@@ -162,6 +334,141 @@ pub struct Withdrawal {
 
     /// On-Chain Account to credited.
     pub receiver: OnIdentity,
+
+    /// Relayer fee that is paid to the sender of the withdrawal.
+    ///
+    /// Comes out of the outcome of the participant, the receiver is
+    /// credited with the rest. Omitted from the encoding when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fee: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Authorization of a watcher to refute disputes on behalf of a participant.
+///
+/// This is signed by the off-chain participant, see
+/// [crate::msg::ExecuteMsg::Dispute].
+pub struct WatcherAuth {
+    /// Channel that the watcher guards.
+    pub channel_id: ChannelId,
+    /// Off-chain participant that pays the reward.
+    pub part: OffIdentity,
+    /// On-chain account of the watcher.
+    pub watcher: OnIdentity,
+    /// Reward for a refutation, paid from the outcome of the participant.
+    pub reward: Vec<Coin>,
+    /// End of the authorization.
+    pub expiry: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Change of the funds of an open channel.
+///
+/// This is signed by all off-chain participants, see
+/// [crate::msg::ExecuteMsg::Splice].
+pub struct Splice {
+    /// State of the channel after the splice.
+    ///
+    /// Becomes the checkpoint of the channel, older states can not be used
+    /// on-chain afterwards.
+    pub state: State,
+    /// Funds that are added to the deposits of participants.
+    pub deposits: Vec<SpliceDeposit>,
+    /// Funds that are paid out of the deposits of participants.
+    pub payouts: Vec<SplicePayout>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Deposit of a [Splice].
+pub struct SpliceDeposit {
+    /// Index of the participant in [Params::participants].
+    pub part: PartIdx,
+    pub amount: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Payout of a [Splice].
+pub struct SplicePayout {
+    /// Index of the participant in [Params::participants] to debit.
+    pub part: PartIdx,
+    /// On-Chain Account to credit.
+    pub receiver: OnIdentity,
+    pub amount: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Move of the outcome of a concluded channel into a new channel.
+///
+/// This is signed by all off-chain participants of the old channel, see
+/// [crate::msg::ExecuteMsg::Rollover].
+pub struct Rollover {
+    pub old_channel: ChannelId,
+    pub new_channel: ChannelId,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Replacement of the off-chain identity of a participant.
+///
+/// This is signed by all off-chain participants, see
+/// [crate::msg::ExecuteMsg::Novate].
+/// The funding id of the participant stays the same, only the identity that
+/// signs on its behalf changes.
+pub struct Novation {
+    pub channel_id: ChannelId,
+    /// Index of the participant in [Params::participants].
+    pub part: PartIdx,
+    /// Identity that currently signs for the participant.
+    pub old: OffIdentity,
+    /// Identity that signs for the participant from now on.
+    pub new: OffIdentity,
+    /// Must be higher than the nonce of every novation that was applied to
+    /// the participant before, see [crate::storage::NOVATION_NONCES].
+    pub nonce: Uint64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Extension of the timeout of a running dispute.
+///
+/// This is signed by all off-chain participants, see
+/// [crate::msg::ExecuteMsg::ExtendDispute].
+pub struct DisputeExtension {
+    pub channel_id: ChannelId,
+    /// Version of the registered state.
+    pub version: Version,
+    /// New timeout of the dispute.
+    pub timeout: Timestamp,
+    /// Must be higher than the nonce of every extension or cancellation
+    /// that was applied to the channel before, see
+    /// [crate::storage::DISPUTE_NONCES].
+    pub nonce: Uint64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Cancellation of a running dispute.
+///
+/// This is signed by all off-chain participants, see
+/// [crate::msg::ExecuteMsg::CancelDispute].
+pub struct DisputeCancellation {
+    pub channel_id: ChannelId,
+    /// Version of the registered state.
+    pub version: Version,
+    /// See [DisputeExtension::nonce].
+    pub nonce: Uint64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Bond that was attached to the opening of a dispute.
+pub struct Bond {
+    /// Account that opened the dispute.
+    pub owner: OnIdentity,
+    pub amount: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+/// Reward that a watcher earned by refuting a dispute.
+pub struct Reward {
+    pub watcher: OnIdentity,
+    pub amount: Vec<Coin>,
 }
 
 impl Params {
@@ -170,6 +477,54 @@ impl Params {
         let h = hash(self, vec![])?;
         Ok(h.finalize().as_slice().into())
     }
+    /// Returns whether the channel expired at `now`, see [Params::expires_at].
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+    /// Checks that these Params are well-formed.
+    ///
+    /// Duplicate participants would share a [FundingId] and are therefore
+    /// rejected.
+    pub fn validate(&self) -> Result<(), ContractError> {
+        ensure!(!self.nonce.is_empty(), ContractError::InvalidNonce {});
+        ensure!(
+            !self.participants.is_empty(),
+            ContractError::InvalidParticipantNum {}
+        );
+        for (i, part) in self.participants.iter().enumerate() {
+            ensure!(part.is_well_formed(), ContractError::InvalidIdentity {});
+            ensure!(
+                !self.participants[..i].contains(part),
+                ContractError::DuplicateParticipant {}
+            );
+        }
+        if let Some(arbiter) = &self.arbiter {
+            ensure!(
+                arbiter.identity.is_well_formed(),
+                ContractError::InvalidIdentity {}
+            );
+        }
+        Ok(())
+    }
+    /// Checks that these Params are within `limits`.
+    pub fn validate_limits(&self, limits: &ChannelLimits) -> Result<(), ContractError> {
+        ensure!(
+            self.participants.len() <= limits.max_participants as usize,
+            ContractError::InvalidParticipantNum {}
+        );
+        ensure!(
+            self.dispute_duration >= limits.min_dispute_duration
+                && self.dispute_duration <= limits.max_dispute_duration,
+            ContractError::InvalidDisputeDuration {}
+        );
+        if let Some(arbiter) = &self.arbiter {
+            ensure!(
+                arbiter.delay <= limits.max_dispute_duration,
+                ContractError::InvalidArbiterDelay {}
+            );
+        }
+        Ok(())
+    }
 }
 
 impl From<Vec<Coin>> for WrappedBalance {
@@ -186,26 +541,69 @@ impl From<WrappedBalance> for Vec<Coin> {
     }
 }
 
-impl Add<&WrappedBalance> for WrappedBalance {
-    type Output = Self;
-
-    fn add(mut self, other: &Self) -> Self::Output {
-        self.0 += other.0.clone();
-        self
-    }
-}
-
 impl WrappedBalance {
+    /// Returns `self + other` or fails with the first denom that overflows.
+    pub fn checked_add(&self, other: &WrappedBalance) -> Result<Self, ContractError> {
+        let mut coins = self.0 .0.clone();
+        for coin in other.0 .0.iter() {
+            match coins.iter_mut().find(|c| c.denom == coin.denom) {
+                Some(c) => {
+                    c.amount = c.amount.checked_add(coin.amount).map_err(|_| {
+                        ContractError::BalanceOverflow {
+                            denom: coin.denom.clone(),
+                        }
+                    })?
+                }
+                None => coins.push(coin.clone()),
+            }
+        }
+        Ok(coins.into())
+    }
+    /// Returns `self - other` or fails with the first denom that underflows.
+    pub fn checked_sub(&self, other: &WrappedBalance) -> Result<Self, ContractError> {
+        let mut coins = self.0 .0.clone();
+        for coin in other.0 .0.iter().filter(|c| !c.amount.is_zero()) {
+            let underflow = || ContractError::BalanceUnderflow {
+                denom: coin.denom.clone(),
+            };
+            let c = coins
+                .iter_mut()
+                .find(|c| c.denom == coin.denom)
+                .ok_or_else(underflow)?;
+            c.amount = c.amount.checked_sub(coin.amount).map_err(|_| underflow())?;
+        }
+        Ok(coins.into())
+    }
     /// Models `self >= b`.
     /// Defines a non-strict partial order in the mathematical sense since
     /// there exist `a` and `b` where `¬(a >= b) ^ ¬(b >= a)`.
     /// Only works with normalized inputs.
     pub fn greater_or_equal(&self, b: &WrappedBalance) -> bool {
-        b.0 .0.iter().map(|b| self.0.has(b)).all(|x| x)
+        b.0 .0.iter().all(|b| self.0.has(b))
     }
 }
 
 impl State {
+    /// Checks that this State is within `limits`.
+    pub fn validate_limits(&self, limits: &ChannelLimits) -> Result<(), ContractError> {
+        let mut denoms: Vec<&str> = self
+            .balances
+            .iter()
+            .flat_map(|bals| bals.0.iter().map(|c| c.denom.as_str()))
+            .collect();
+        denoms.sort_unstable();
+        denoms.dedup();
+        ensure!(
+            denoms.len() <= limits.max_denoms as usize,
+            ContractError::TooManyDenoms {}
+        );
+        Ok(())
+    }
+    /// Calculates the hash of this State.
+    pub fn hash(&self) -> Result<Hash, ContractError> {
+        let h = hash(self, vec![])?;
+        Ok(h.finalize().as_slice().into())
+    }
     /// Verifies that `from` signed this State.
     pub fn verify(
         &self,
@@ -216,9 +614,13 @@ impl State {
         verify(self, from, sig, api)
     }
     /// Verifies that all participants signed this State.
+    ///
+    /// `signers` are the identities that currently sign for the
+    /// participants, see [crate::storage::load_signers].
     pub fn verify_fully_signed(
         &self,
         params: &Params,
+        signers: &[OffIdentity],
         sigs: &[Sig],
         api: &dyn Api,
     ) -> Result<(), ContractError> {
@@ -232,20 +634,204 @@ impl State {
         ensure!(!sigs.is_empty(), ContractError::InvalidSignatureNum {});
         // Check the state signatures.
         ensure!(
-            sigs.len() == params.participants.len(),
+            sigs.len() == signers.len(),
             ContractError::WrongSignatureNum {}
         );
-        for (i, sig) in sigs.iter().enumerate() {
-            self.verify(sig, &params.participants[i], api)?;
+        for (sig, signer) in sigs.iter().zip(signers.iter()) {
+            self.verify(sig, signer, api)?;
         }
         Ok(())
     }
+    /// Verifies that the [Params::arbiter] signed this State.
+    ///
+    /// No participant signatures may be given alongside.
+    pub fn verify_arbitrated(
+        &self,
+        params: &Params,
+        sigs: &[Sig],
+        arbiter_sig: &Sig,
+        api: &dyn Api,
+    ) -> Result<(), ContractError> {
+        let arbiter = params
+            .arbiter
+            .as_ref()
+            .ok_or(ContractError::ArbiterNotSet {})?;
+        ensure!(
+            self.channel_id == params.channel_id()?,
+            ContractError::WrongChannelId {}
+        );
+        ensure!(sigs.is_empty(), ContractError::WrongSignatureNum {});
+        self.verify(arbiter_sig, &arbiter.identity, api)
+            .map_err(|_| ContractError::WrongArbiterSignature {})
+    }
+}
+
+impl Config {
+    /// Returns whether `denom` can be deposited.
+    pub fn allows_denom(&self, denom: &str) -> bool {
+        match &self.allowed_denoms {
+            Some(denoms) => denoms.iter().any(|d| d == denom),
+            None => true,
+        }
+    }
+    /// Returns the cap of a single funding id for `denom`, if any.
+    pub fn funding_cap(&self, denom: &str) -> Option<Uint128> {
+        find_amount(&self.funding_caps, denom)
+    }
+    /// Returns the bond that opening a dispute of `params` requires.
+    pub fn dispute_bond(&self, params: &Params) -> WrappedBalance {
+        match params.bond.is_empty() {
+            true => self.dispute_bond.clone().into(),
+            false => params.bond.clone().into(),
+        }
+    }
+    /// Returns the cap of the contract for `denom`, if any.
+    pub fn denom_cap(&self, denom: &str) -> Option<Uint128> {
+        find_amount(&self.denom_caps, denom)
+    }
+}
+
+/// Returns the amount of `denom` in `coins`, if any.
+pub fn find_amount(coins: &[Coin], denom: &str) -> Option<Uint128> {
+    coins.iter().find(|c| c.denom == denom).map(|c| c.amount)
+}
+
+impl RegisteredState {
+    /// Creates a record of `state` which omits the state if `compact` is set.
+    pub fn new(state: &State, compact: bool) -> Result<Self, ContractError> {
+        Ok(Self {
+            version: state.version,
+            hash: state.hash()?,
+            state: if compact { None } else { Some(state.clone()) },
+        })
+    }
+    /// Returns whether `state` is the registered state.
+    pub fn matches(&self, state: &State) -> Result<bool, ContractError> {
+        Ok(self.hash == state.hash()?)
+    }
+    /// Returns the registered state.
+    ///
+    /// Uses the stored state if there is one, otherwise `preimage` which
+    /// must then hash to the registered hash.
+    pub fn preimage(&self, preimage: Option<&State>) -> Result<State, ContractError> {
+        if let Some(state) = &self.state {
+            return Ok(state.clone());
+        }
+        let preimage = preimage.ok_or(ContractError::MissingPreimage {})?;
+        ensure!(self.matches(preimage)?, ContractError::WrongPreimage {});
+        Ok(preimage.clone())
+    }
+}
+
+impl WatcherAuth {
+    /// Verifies that `from` signed this WatcherAuth on behalf of the
+    /// participant.
+    pub fn verify(
+        &self,
+        sig: &Sig,
+        from: &OffIdentity,
+        api: &dyn Api,
+    ) -> Result<(), ContractError> {
+        verify(self, from, sig, api)
+    }
+}
+
+impl Splice {
+    /// Verifies that all participants signed this Splice.
+    ///
+    /// See [State::verify_fully_signed] for `signers`.
+    pub fn verify_fully_signed(
+        &self,
+        params: &Params,
+        signers: &[OffIdentity],
+        sigs: &[Sig],
+        api: &dyn Api,
+    ) -> Result<(), ContractError> {
+        ensure!(
+            self.state.channel_id == params.channel_id()?,
+            ContractError::WrongChannelId {}
+        );
+        verify_all(self, signers, sigs, api)
+    }
+}
+
+impl Rollover {
+    /// Verifies that all `signers` signed this Rollover.
+    pub fn verify_fully_signed(
+        &self,
+        signers: &[OffIdentity],
+        sigs: &[Sig],
+        api: &dyn Api,
+    ) -> Result<(), ContractError> {
+        verify_all(self, signers, sigs, api)
+    }
+}
+
+impl Novation {
+    /// Verifies that all `signers` signed this Novation.
+    pub fn verify_fully_signed(
+        &self,
+        signers: &[OffIdentity],
+        sigs: &[Sig],
+        api: &dyn Api,
+    ) -> Result<(), ContractError> {
+        verify_all(self, signers, sigs, api)
+    }
+}
+
+impl DisputeExtension {
+    /// Verifies that all `signers` signed this DisputeExtension.
+    pub fn verify_fully_signed(
+        &self,
+        signers: &[OffIdentity],
+        sigs: &[Sig],
+        api: &dyn Api,
+    ) -> Result<(), ContractError> {
+        verify_all(self, signers, sigs, api)
+    }
+}
+
+impl DisputeCancellation {
+    /// Verifies that all `signers` signed this DisputeCancellation.
+    pub fn verify_fully_signed(
+        &self,
+        signers: &[OffIdentity],
+        sigs: &[Sig],
+        api: &dyn Api,
+    ) -> Result<(), ContractError> {
+        verify_all(self, signers, sigs, api)
+    }
+}
+
+/// Verifies that each of the `signers` signed `obj`, in order.
+fn verify_all<T: Serialize>(
+    obj: &T,
+    signers: &[OffIdentity],
+    sigs: &[Sig],
+    api: &dyn Api,
+) -> Result<(), ContractError> {
+    ensure!(
+        sigs.len() == signers.len(),
+        ContractError::WrongSignatureNum {}
+    );
+    for (sig, signer) in sigs.iter().zip(signers.iter()) {
+        verify(obj, signer, sig, api)?;
+    }
+    Ok(())
 }
 
 impl Withdrawal {
     /// Verifies that `from` signed this Withdrawal.
-    pub fn verify(&self, sig: &Sig, api: &dyn Api) -> Result<(), ContractError> {
-        verify(self, &self.part, sig, api)
+    ///
+    /// `from` is the participant itself unless its key was replaced, see
+    /// [crate::storage::load_authority].
+    pub fn verify(
+        &self,
+        sig: &Sig,
+        from: &OffIdentity,
+        api: &dyn Api,
+    ) -> Result<(), ContractError> {
+        verify(self, from, sig, api)
     }
     // Calculates the funding id from this Withdrawal.
     pub fn funding_id(&self) -> Result<FundingId, ContractError> {